@@ -0,0 +1,186 @@
+use crate::hex;
+use clap::Args;
+use eldritchwire::{EldritchError, FrameParser};
+use std::{
+    error::Error,
+    fs,
+    io::{self, Read, Write},
+    path::PathBuf,
+};
+
+/// Decode frame packets and print every command in them.
+#[derive(Args, Debug)]
+pub struct DecodeArgs {
+    /// Capture to decode, hex is read from stdin when omitted or `-`.
+    pub input: Option<PathBuf>,
+    /// The input is a raw binary capture instead of one hex packet per line.
+    #[arg(long)]
+    pub binary: bool,
+}
+
+/// Returns `false` if any command failed to decode.
+pub fn run(args: DecodeArgs) -> Result<bool, Box<dyn Error>> {
+    let input = match args.input.filter(|path| path.as_os_str() != "-") {
+        Some(path) => fs::read(&path).map_err(|err| format!("{}: {err}", path.display()))?,
+        None => {
+            let mut input = Vec::new();
+            io::stdin().read_to_end(&mut input)?;
+            input
+        }
+    };
+    let packets = if args.binary {
+        split_capture(&input)
+    } else {
+        hex::parse_lines(&String::from_utf8(input)?)?
+    };
+
+    let mut out = io::stdout().lock();
+    let mut errors = 0;
+    for (idx, packet) in packets.iter().enumerate() {
+        errors += write_packet(&mut out, idx + 1, packet)?;
+    }
+    Ok(errors == 0)
+}
+
+/// Splits a binary capture of back to back commands into frame packets of at most 255 bytes,
+/// following the command lengths in the headers.
+pub fn split_capture(capture: &[u8]) -> Vec<Vec<u8>> {
+    let mut packets: Vec<Vec<u8>> = Vec::new();
+    let mut packet: Vec<u8> = Vec::new();
+    let mut rest = capture;
+
+    while !rest.is_empty() {
+        let command_length = usize::from(rest.get(1).copied().unwrap_or_default());
+        let size = (4 + command_length + (4 - command_length % 4) % 4).min(rest.len());
+        if !packet.is_empty() && packet.len() + size > 255 {
+            packets.push(std::mem::take(&mut packet));
+        }
+        packet.extend_from_slice(&rest[..size]);
+        rest = &rest[size..];
+    }
+
+    if !packet.is_empty() {
+        packets.push(packet);
+    }
+    packets
+}
+
+/// Writes a table of the commands in a packet and returns how many failed to decode.
+pub fn write_packet(out: &mut impl Write, number: usize, packet: &[u8]) -> io::Result<usize> {
+    writeln!(out, "packet {number}, {} bytes", packet.len())?;
+
+    let mut parser = match FrameParser::new(packet) {
+        Ok(parser) => parser,
+        Err(err) => {
+            writeln!(out, "  error: {err}")?;
+            return Ok(1);
+        }
+    };
+
+    writeln!(out, "  offset  device  category  parameter  command")?;
+    let mut errors = 0;
+    while let Some(command_data) = parser.next_command_data() {
+        let error = match command_data {
+            Ok(command_data) => match command_data.to_addressed_command() {
+                Ok(command) => {
+                    writeln!(
+                        out,
+                        "  {:>6}  {:>6}  {:<8}  {:<9}  {}",
+                        command_data.offset,
+                        command.device_id,
+                        format!("{:#04x}", command.command.category()),
+                        format!("{:#04x}", command.command.parameter()),
+                        command.command
+                    )?;
+                    continue;
+                }
+                Err(err) => err,
+            },
+            Err(err) => err,
+        };
+        errors += 1;
+        write_error(out, packet, &error)?;
+    }
+    Ok(errors)
+}
+
+/// The error followed by the packet with the offending byte marked.
+fn write_error(out: &mut impl Write, packet: &[u8], error: &EldritchError) -> io::Result<()> {
+    let (offset, index) = error
+        .context()
+        .map_or((0, 0), |context| (context.offset, context.index));
+    writeln!(out, "  {offset:>6}  error: {}", error.root_cause())?;
+    writeln!(out, "          {}", hex::format_line(packet))?;
+    writeln!(out, "          {:width$}^^", "", width = index * 3)
+}
+
+#[cfg(test)]
+mod decode_test {
+    use super::*;
+
+    fn decode(packet: &[u8]) -> (String, usize) {
+        let mut out = Vec::new();
+        let errors = write_packet(&mut out, 1, packet).unwrap();
+        (String::from_utf8(out).unwrap(), errors)
+    }
+
+    #[test]
+    fn decode_packet() {
+        let packet =
+            hex::parse_line("04 04 00 00 00 01 00 00 04 06 00 00 04 02 80 01 33 01 00 00").unwrap();
+        assert_eq!(
+            decode(&packet),
+            (
+                String::from(
+                    "packet 1, 20 bytes\n\
+                     \x20 offset  device  category  parameter  command\n\
+                     \x20      0       4  0x00      0x01       lens.instantaneous_auto_focus\n\
+                     \x20      8       4  0x04      0x02       display.zebra_level += 0.15\n"
+                ),
+                0
+            )
+        );
+    }
+
+    #[test]
+    fn decode_marks_bad_command() {
+        let packet = hex::parse_line("00 04 00 00 00 01 00 00 03 04 00 00 00 7F 00 00").unwrap();
+        let (output, errors) = decode(&packet);
+
+        assert_eq!(errors, 1);
+        let lines: Vec<_> = output.lines().collect();
+        assert!(lines[3].starts_with("       8  error: Unknown parameter: 0x7f"));
+        assert_eq!(
+            lines[lines.len() - 2..],
+            [
+                "          00 04 00 00 00 01 00 00 03 04 00 00 00 7F 00 00",
+                "                                                 ^^",
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_packet_too_large() {
+        let (output, errors) = decode(&[0x00; 256]);
+        assert_eq!(errors, 1);
+        assert_eq!(
+            output,
+            "packet 1, 256 bytes\n  error: Blanking packet is larger then 255 bytes\n"
+        );
+    }
+
+    #[test]
+    fn split_capture_on_command_boundaries() {
+        let command = [
+            0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x01, 0x00, 0x00, 0x00,
+        ];
+        let capture = command.repeat(22);
+        let packets = split_capture(&capture);
+
+        assert_eq!(
+            packets.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![252, 12]
+        );
+        assert_eq!(packets.concat(), capture);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::hex;
+use clap::Args;
+use eldritchwire::{encode_frame_packets, AddressedCommand};
+use std::{
+    error::Error,
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+/// Encode commands written as `cam 1 lens.focus = 0.5` into frame packets.
+#[derive(Args, Debug)]
+pub struct EncodeArgs {
+    /// Commands to encode, read one per line from stdin when none are given.
+    pub commands: Vec<String>,
+    /// Write the packets to a file in the `MockI2c` format instead of stdout.
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+pub fn run(args: EncodeArgs) -> Result<bool, Box<dyn Error>> {
+    let commands = if args.commands.is_empty() {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        parse_commands(input.lines())?
+    } else {
+        parse_commands(args.commands.iter().map(String::as_str))?
+    };
+
+    let packets = encode(&commands)?;
+    match args.output {
+        Some(path) => {
+            fs::write(&path, packets).map_err(|err| format!("{}: {err}", path.display()))?
+        }
+        None => print!("{packets}"),
+    }
+    Ok(true)
+}
+
+/// Skips blank lines and `#` comments.
+fn parse_commands<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<AddressedCommand>, String> {
+    lines
+        .into_iter()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.parse().map_err(|err| format!("`{line}`: {err}")))
+        .collect()
+}
+
+/// One hex line per frame packet, commands are split over as few packets as fit.
+fn encode(commands: &[AddressedCommand]) -> Result<String, Box<dyn Error>> {
+    Ok(encode_frame_packets(commands)?
+        .iter()
+        .map(|packet| hex::format_line(packet) + "\n")
+        .collect())
+}
+
+#[cfg(test)]
+mod encode_test {
+    use super::*;
+
+    fn encode_lines(lines: &[&str]) -> Result<String, String> {
+        let commands = parse_commands(lines.iter().copied())?;
+        encode(&commands).map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn encode_commands() {
+        assert_eq!(
+            encode_lines(&[
+                "cam 1 lens.focus = 0.5",
+                "",
+                "# ISO",
+                "cam 1 video.iso = 800"
+            ]),
+            Ok(String::from(
+                "01 06 00 00 00 00 80 00 00 04 00 00 01 08 00 00 01 0E 03 00 20 03 00 00\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn encode_splits_packets() {
+        let commands = vec!["cam 2 lens.instantaneous_auto_focus"; 32];
+        let packets = encode_lines(&commands).unwrap();
+
+        assert_eq!(packets.lines().count(), 2);
+        assert_eq!(packets.lines().last(), Some("02 04 00 00 00 01 00 00"));
+    }
+
+    #[test]
+    fn encode_rejects_out_of_bounds() {
+        assert_eq!(
+            encode_lines(&["cam 1 lens.focus = 1.5"]),
+            Err(String::from(
+                "`cam 1 lens.focus = 1.5`: A Data element is out of bounds for the specified command"
+            ))
+        );
+    }
+}
@@ -0,0 +1,74 @@
+//! Hex dumps in the `MockI2c` test data format, one frame packet per line of space separated bytes.
+
+/// Bytes of a single line, tokens may be separated by spaces or commas and prefixed with `0x`.
+pub fn parse_line(line: &str) -> Result<Vec<u8>, String> {
+    line.split(|ch: char| ch.is_whitespace() || ch == ',')
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            let digits = token
+                .strip_prefix("0x")
+                .or_else(|| token.strip_prefix("0X"))
+                .unwrap_or(token);
+            if digits.len() > 2 {
+                return Err(format!("`{token}` is not a single byte"));
+            }
+            u8::from_str_radix(digits, 16).map_err(|_| format!("`{token}` is not a hex byte"))
+        })
+        .collect()
+}
+
+/// One packet per non empty line.
+pub fn parse_lines(text: &str) -> Result<Vec<Vec<u8>>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| parse_line(line).map_err(|err| format!("line {}: {err}", idx + 1)))
+        .collect()
+}
+
+pub fn format_line(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod hex_test {
+    use super::*;
+
+    #[test]
+    fn parse_mock_i2c_format() {
+        assert_eq!(
+            parse_lines("04 04 00 00 00 01 00 00\n\nFF 05 00 00 00 06 00 00 01 00 00 00\n"),
+            Ok(vec![
+                vec![0x04, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00],
+                vec![0xff, 0x05, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00],
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_prefixed_and_comma_separated() {
+        assert_eq!(parse_line("0x04, 0x0a,ff"), Ok(vec![0x04, 0x0a, 0xff]));
+    }
+
+    #[test]
+    fn parse_invalid_byte() {
+        assert_eq!(
+            parse_lines("04 04\n04 0g"),
+            Err(String::from("line 2: `0g` is not a hex byte"))
+        );
+        assert_eq!(
+            parse_line("0404"),
+            Err(String::from("`0404` is not a single byte"))
+        );
+    }
+
+    #[test]
+    fn format_round_trip() {
+        let line = "FF 09 00 00 01 00 01 00";
+        assert_eq!(format_line(&parse_line(line).unwrap()), line);
+    }
+}
@@ -0,0 +1,40 @@
+mod decode;
+mod encode;
+mod hex;
+mod monitor;
+mod replay;
+
+use clap::{Parser, Subcommand};
+use std::process::ExitCode;
+
+/// Tools for the Blackmagic SDI camera control protocol.
+#[derive(Parser, Debug)]
+#[command(name = "eldritch", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    Decode(decode::DecodeArgs),
+    Encode(encode::EncodeArgs),
+    Monitor(monitor::MonitorArgs),
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Decode(args) => decode::run(args),
+        Command::Encode(args) => encode::run(args),
+        Command::Monitor(args) => monitor::run(args),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,408 @@
+use crate::{hex, replay::Replay};
+use clap::Args;
+use eldritch_shield::{shield::EldritchShield, traits::I2cTransport};
+use eldritchwire::{
+    commands::Command, AddressedCommand, ChangeNotifier, EldritchError, FrameParser, TallyFrame,
+};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Watch incoming control and tally data on the SDI shield.
+#[derive(Args, Debug)]
+pub struct MonitorArgs {
+    /// I2C bus the shield is connected to, needs the `rppal` feature.
+    #[arg(long, default_value = "/dev/i2c-1")]
+    pub bus: PathBuf,
+    /// I2C address of the shield.
+    #[arg(long, default_value = "0x6e", value_parser = parse_u8)]
+    pub address: u8,
+    /// Replay a capture in the `MockI2c` format instead of reading from the shield.
+    #[arg(long)]
+    pub replay: Option<PathBuf>,
+    /// Only show commands for this device id, can be repeated.
+    #[arg(long = "device", value_parser = parse_u8)]
+    pub devices: Vec<u8>,
+    /// Only show this category, by number or group name like `lens`, can be repeated.
+    #[arg(long = "category", value_parser = parse_category)]
+    pub categories: Vec<u8>,
+    /// Print a scrolling log instead of a table per camera.
+    #[arg(long)]
+    pub log: bool,
+    /// Append every incoming control packet to a capture file in the `MockI2c` format.
+    #[arg(long)]
+    pub record: Option<PathBuf>,
+    /// Milliseconds to wait between polls when the shield has no new data.
+    #[arg(long, default_value_t = 20)]
+    pub interval: u64,
+}
+
+pub fn run(args: MonitorArgs) -> Result<bool, Box<dyn Error>> {
+    let record = args
+        .record
+        .as_ref()
+        .map(|path| {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|err| format!("{}: {err}", path.display()))
+        })
+        .transpose()?;
+    let mut monitor = Monitor::new(
+        io::stdout().lock(),
+        Filter {
+            devices: args.devices,
+            categories: args.categories,
+        },
+        args.log,
+        record,
+    );
+
+    if let Some(path) = &args.replay {
+        poll(
+            &mut EldritchShield::new(Replay::from_file(path)?, args.address),
+            &mut monitor,
+            None,
+        )?;
+    } else {
+        let mut shield = open_shield(&args.bus, args.address)?;
+        let interval = Duration::from_millis(args.interval);
+        poll(&mut shield, &mut monitor, Some(interval))?;
+    }
+    Ok(true)
+}
+
+#[cfg(feature = "rppal")]
+fn open_shield(
+    bus: &std::path::Path,
+    address: u8,
+) -> Result<EldritchShield<rppal::i2c::I2c>, Box<dyn Error>> {
+    let bus_number = bus
+        .to_str()
+        .and_then(|bus| bus.strip_prefix("/dev/i2c-"))
+        .and_then(|bus| bus.parse().ok())
+        .ok_or_else(|| format!("{} is not an I2C bus", bus.display()))?;
+    let mut i2c = rppal::i2c::I2c::with_bus(bus_number)?;
+    i2c.set_slave_address(u16::from(address))?;
+    let mut shield = EldritchShield::new(i2c, address);
+    shield.verify_device()?;
+    Ok(shield)
+}
+
+#[cfg(not(feature = "rppal"))]
+fn open_shield(
+    _bus: &std::path::Path,
+    _address: u8,
+) -> Result<EldritchShield<Replay>, Box<dyn Error>> {
+    Err("eldritch was built without the `rppal` feature, only --replay is available".into())
+}
+
+/// Reads packets whenever the shield clears an arm flag. Without an interval polling stops as soon
+/// as there is no new data, which is how a replay ends.
+fn poll<I2C, E>(
+    shield: &mut EldritchShield<I2C>,
+    monitor: &mut Monitor<impl Write>,
+    interval: Option<Duration>,
+) -> Result<(), Box<dyn Error>>
+where
+    I2C: I2cTransport<Error = E>,
+    E: Error + 'static,
+{
+    shield.incoming_control_arm()?;
+    shield.incoming_tally_arm()?;
+    loop {
+        let mut idle = true;
+        // A cleared arm flag with no data behind it is skipped, the shield only clears the flag
+        // once it has filled the buffer.
+        if !shield.is_incoming_control_armed()? && shield.get_incoming_control_length()? > 0 {
+            let packet = shield.get_incoming_control_data()?;
+            shield.incoming_control_arm()?;
+            monitor.control_packet(&packet)?;
+            idle = false;
+        }
+        if !shield.incoming_tally_armed()? && shield.get_incoming_tally_length()? > 0 {
+            let tally = shield.get_incoming_tally_data()?;
+            shield.incoming_tally_arm()?;
+            monitor.tally(TallyFrame::from_bytes(&tally))?;
+            idle = false;
+        }
+
+        match interval {
+            _ if !idle => {}
+            Some(interval) => thread::sleep(interval),
+            None => return Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Filter {
+    devices: Vec<u8>,
+    categories: Vec<u8>,
+}
+
+impl Filter {
+    fn device(&self, device_id: u8) -> bool {
+        self.devices.is_empty() || self.devices.contains(&device_id)
+    }
+
+    fn category(&self, category: u8) -> bool {
+        self.categories.is_empty() || self.categories.contains(&category)
+    }
+
+    fn matches(&self, command: &AddressedCommand) -> bool {
+        self.device(command.device_id) && self.category(command.command.category())
+    }
+}
+
+struct Monitor<W> {
+    out: W,
+    filter: Filter,
+    log: bool,
+    record: Option<File>,
+    notifier: ChangeNotifier,
+    tally: Option<TallyFrame>,
+    errors: BTreeMap<u8, String>,
+    start: Instant,
+}
+
+impl<W: Write> Monitor<W> {
+    fn new(out: W, filter: Filter, log: bool, record: Option<File>) -> Self {
+        Self {
+            out,
+            filter,
+            log,
+            record,
+            notifier: ChangeNotifier::new(),
+            tally: None,
+            errors: BTreeMap::new(),
+            start: Instant::now(),
+        }
+    }
+
+    fn control_packet(&mut self, packet: &[u8]) -> io::Result<()> {
+        if let Some(record) = &mut self.record {
+            writeln!(record, "{}", hex::format_line(packet))?;
+        }
+
+        let mut changed = false;
+        let mut parser = match FrameParser::new(packet) {
+            Ok(parser) => parser,
+            Err(err) => return self.error(0, &err),
+        };
+        while let Some(command_data) = parser.next_command_data() {
+            match command_data.and_then(|command_data| command_data.to_addressed_command()) {
+                Ok(command) => {
+                    changed |= !self.notifier.apply(&command).is_empty();
+                    if self.log && self.filter.matches(&command) {
+                        self.log_line(&command.to_string())?;
+                    }
+                }
+                Err(err) => {
+                    let device_id = err.context().map_or(0, |context| context.device_id);
+                    self.error(device_id, &err)?;
+                }
+            }
+        }
+
+        if changed && !self.log {
+            self.draw_table()?;
+        }
+        Ok(())
+    }
+
+    /// Errors are logged as they happen, the table keeps the last one of each camera.
+    fn error(&mut self, device_id: u8, err: &EldritchError) -> io::Result<()> {
+        if !self.filter.device(device_id) {
+            return Ok(());
+        }
+        if self.log {
+            self.log_line(&format!("cam {device_id} error: {err}"))
+        } else {
+            self.errors.insert(device_id, err.to_string());
+            self.draw_table()
+        }
+    }
+
+    fn tally(&mut self, tally: TallyFrame) -> io::Result<()> {
+        if self.log {
+            let line = format!("tally: {}", self.format_tally(&tally));
+            self.log_line(&line)
+        } else {
+            self.tally = Some(tally);
+            self.draw_table()
+        }
+    }
+
+    /// Cameras on program or preview, e.g. `cam 1 program, cam 3 preview`.
+    fn format_tally(&self, tally: &TallyFrame) -> String {
+        let cameras: Vec<_> = tally
+            .iter()
+            .filter(|camera| self.filter.device(camera.camera))
+            .filter_map(|camera| match (camera.program, camera.preview) {
+                (true, true) => Some(format!("cam {} program+preview", camera.camera)),
+                (true, false) => Some(format!("cam {} program", camera.camera)),
+                (false, true) => Some(format!("cam {} preview", camera.camera)),
+                (false, false) => None,
+            })
+            .collect();
+        if cameras.is_empty() {
+            String::from("clear")
+        } else {
+            cameras.join(", ")
+        }
+    }
+
+    fn log_line(&mut self, line: &str) -> io::Result<()> {
+        let elapsed = self.start.elapsed().as_secs_f32();
+        writeln!(self.out, "[{elapsed:>9.3}s] {line}")
+    }
+
+    fn draw_table(&mut self) -> io::Result<()> {
+        // Clear the screen and move the cursor to the top left.
+        write!(self.out, "\x1b[2J\x1b[H")?;
+        if let Some(tally) = &self.tally {
+            writeln!(self.out, "tally: {}", self.format_tally(tally))?;
+        }
+        for (device_id, camera) in self.notifier.registry().iter() {
+            if !self.filter.device(device_id) {
+                continue;
+            }
+            writeln!(self.out, "cam {device_id}")?;
+            for value in camera.values() {
+                if self.filter.category(value.category()) {
+                    writeln!(self.out, "  {value}")?;
+                }
+            }
+            if let Some(error) = self.errors.get(&device_id) {
+                writeln!(self.out, "  last error: {error}")?;
+            }
+        }
+        self.out.flush()
+    }
+}
+
+fn parse_u8(value: &str) -> Result<u8, String> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u8::from_str_radix(hex, 16),
+        None => value.parse(),
+    }
+    .map_err(|_| format!("`{value}` is not a number from 0 to 255"))
+}
+
+fn parse_category(value: &str) -> Result<u8, String> {
+    Command::GROUPS
+        .iter()
+        .find(|(name, _)| *name == value)
+        .map(|(_, category)| *category)
+        .map_or_else(|| parse_u8(value), Ok)
+}
+
+#[cfg(test)]
+mod monitor_test {
+    use super::*;
+
+    const TEST_DATA: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../eldritch_shield/src/transport_adapters/test_data.txt"
+    );
+
+    fn replay(filter: Filter, log: bool) -> String {
+        let mut shield = EldritchShield::new(Replay::from_file(TEST_DATA.as_ref()).unwrap(), 0x6e);
+        let mut monitor = Monitor::new(Vec::new(), filter, log, None);
+        poll(&mut shield, &mut monitor, None).unwrap();
+        String::from_utf8(monitor.out).unwrap()
+    }
+
+    #[test]
+    fn log_filters_device_and_category() {
+        let output = replay(
+            Filter {
+                devices: vec![4],
+                categories: vec![0x01, 0x04],
+            },
+            true,
+        );
+        let lines: Vec<_> = output
+            .lines()
+            .map(|line| line.split_once("] ").unwrap().1)
+            .collect();
+
+        assert_eq!(
+            lines,
+            [
+                "cam 4 video.exposure_us = 10000",
+                "cam 4 display.zebra_level += 0.15",
+            ]
+        );
+    }
+
+    #[test]
+    fn table_shows_known_values() {
+        let output = replay(Filter::default(), false);
+        let table = output.rsplit("\x1b[2J\x1b[H").next().unwrap();
+
+        assert_eq!(
+            table,
+            "cam 4\n\
+             \x20 lens.optical_image_stabalization = true\n\
+             \x20 video.video_mode = {frame_rate: 24, m_rate: 1, dimensions: 3, interlaced: 0, color_space: 0}\n\
+             \x20 video.exposure_us = 10000\n"
+        );
+    }
+
+    #[test]
+    fn record_packets() {
+        let path = std::env::temp_dir().join(format!("eldritch-record-{}.txt", std::process::id()));
+        let record = File::create(&path).unwrap();
+        let mut shield = EldritchShield::new(Replay::from_file(TEST_DATA.as_ref()).unwrap(), 0x6e);
+        let mut monitor = Monitor::new(io::sink(), Filter::default(), true, Some(record));
+        poll(&mut shield, &mut monitor, None).unwrap();
+        drop(monitor);
+
+        let recorded = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            hex::parse_lines(&recorded),
+            hex::parse_lines(&std::fs::read_to_string(TEST_DATA).unwrap())
+        );
+    }
+
+    #[test]
+    fn log_tally() {
+        let mut monitor = Monitor::new(Vec::new(), Filter::default(), true, None);
+        monitor
+            .tally(TallyFrame::from_bytes(&[0x01, 0x00, 0x02, 0x03]))
+            .unwrap();
+        monitor.tally(TallyFrame::from_bytes(&[0x00])).unwrap();
+        let output = String::from_utf8(monitor.out).unwrap();
+        let lines: Vec<_> = output
+            .lines()
+            .map(|line| line.split_once("] ").unwrap().1)
+            .collect();
+
+        assert_eq!(
+            lines,
+            [
+                "tally: cam 1 program, cam 3 preview, cam 4 program+preview",
+                "tally: clear",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_category_names() {
+        assert_eq!(parse_category("lens"), Ok(0x00));
+        assert_eq!(parse_category("color_correction"), Ok(0x08));
+        assert_eq!(parse_category("0x0b"), Ok(0x0b));
+        assert_eq!(parse_category("12"), Ok(0x0c));
+        assert!(parse_category("camera").is_err());
+    }
+}
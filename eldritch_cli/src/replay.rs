@@ -0,0 +1,77 @@
+//! Plays a capture back through the shield's incoming control buffer for `monitor --replay`.
+
+use crate::hex;
+use eldritch_shield::{registers, traits::I2cTransport};
+use std::{collections::VecDeque, convert::Infallible, fs, path::Path};
+
+/// An I2C transport that hands out one captured packet per incoming control frame. The incoming
+/// control arm flag stays set once every packet has been read, as if the camera went quiet.
+pub struct Replay {
+    packets: VecDeque<Vec<u8>>,
+}
+
+impl Replay {
+    pub fn new(packets: Vec<Vec<u8>>) -> Self {
+        Self {
+            packets: packets.into(),
+        }
+    }
+
+    /// Reads a capture in the `MockI2c` format, one packet per line.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| hex::parse_lines(&text))
+            .map(Self::new)
+            .map_err(|err| format!("{}: {err}", path.display()))
+    }
+}
+
+impl I2cTransport for Replay {
+    type Error = Infallible;
+
+    fn write(&mut self, _addr: &[u8; 2], _bytes: &[u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn read(&mut self, addr: &[u8; 2], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        buffer.fill(0);
+        if *addr == registers::INCOMING_CONTROL_ARM.address {
+            buffer[0] = u8::from(self.packets.is_empty());
+        } else if *addr == registers::INCOMING_CONTROL_LENGTH.address {
+            buffer[0] = self.packets.front().map_or(0, |packet| packet.len() as u8);
+        } else if *addr == registers::INCOMING_CONTROL_DATA.address {
+            if let Some(packet) = self.packets.pop_front() {
+                let len = packet.len().min(buffer.len());
+                buffer[..len].copy_from_slice(&packet[..len]);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod replay_test {
+    use super::*;
+
+    #[test]
+    fn replay_packets_in_order() {
+        let mut replay = Replay::new(vec![vec![0x01, 0x02], vec![0x03]]);
+        let mut read = |register: &registers::Register| {
+            let mut buffer = vec![0; register.length];
+            replay.read(&register.address, &mut buffer).unwrap();
+            buffer
+        };
+
+        assert_eq!(read(registers::INCOMING_CONTROL_ARM), [0x00]);
+        assert_eq!(read(registers::INCOMING_CONTROL_LENGTH), [0x02]);
+        assert_eq!(
+            read(registers::INCOMING_CONTROL_DATA)[..3],
+            [0x01, 0x02, 0x00]
+        );
+        assert_eq!(read(registers::INCOMING_CONTROL_LENGTH), [0x01]);
+        assert_eq!(read(registers::INCOMING_CONTROL_DATA)[..2], [0x03, 0x00]);
+        assert_eq!(read(registers::INCOMING_CONTROL_ARM), [0x01]);
+        assert_eq!(read(registers::INCOMING_TALLY_LENGTH), [0x00]);
+    }
+}
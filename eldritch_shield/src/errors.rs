@@ -1,10 +1,19 @@
-use std::alloc;
+use crate::registers::Version;
+use std::{alloc, error::Error, fmt};
 #[derive(Debug, Clone, PartialEq)]
 pub enum ShieldError<E> {
     Transport(E),
     InvalidResponse,
     OutOfRange,
     MemoryAllocationError(alloc::LayoutError),
+    /// The device didn't identify itself as a 3G-SDI Shield.
+    UnexpectedIdentity([u8; 4]),
+    UnsupportedHardware(Version),
+    UnsupportedFirmware(Version),
+    /// The shield kept the output arm flag set, the previous frame was never sent.
+    Busy,
+    #[cfg(feature = "eldritchwire")]
+    Wire(eldritchwire::EldritchError),
     // add others as needed
 }
 
@@ -13,3 +22,42 @@ impl<E> From<E> for ShieldError<E> {
         ShieldError::Transport(err)
     }
 }
+
+impl<E: fmt::Display> fmt::Display for ShieldError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShieldError::Transport(err) => write!(f, "I2C transport error: {err}"),
+            ShieldError::InvalidResponse => write!(f, "Shield returned an invalid response"),
+            ShieldError::OutOfRange => write!(f, "Value is out of range for the shield register"),
+            ShieldError::MemoryAllocationError(err) => {
+                write!(f, "Unable to allocate read buffer: {err}")
+            }
+            ShieldError::UnexpectedIdentity(identity) => write!(
+                f,
+                "Expected a 3G-SDI Shield identifying as \"SDIC\", found {:?}",
+                String::from_utf8_lossy(identity)
+            ),
+            ShieldError::UnsupportedHardware(version) => {
+                write!(f, "Shield hardware version {version} is not supported")
+            }
+            ShieldError::UnsupportedFirmware(version) => {
+                write!(f, "Shield firmware version {version} is not supported")
+            }
+            ShieldError::Busy => write!(f, "Shield did not send the previous frame in time"),
+            #[cfg(feature = "eldritchwire")]
+            ShieldError::Wire(err) => write!(f, "Camera control error: {err}"),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for ShieldError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ShieldError::Transport(err) => Some(err),
+            ShieldError::MemoryAllocationError(err) => Some(err),
+            #[cfg(feature = "eldritchwire")]
+            ShieldError::Wire(err) => Some(err),
+            _ => None,
+        }
+    }
+}
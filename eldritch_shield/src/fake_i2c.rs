@@ -0,0 +1,58 @@
+use crate::registers;
+use crate::traits::I2cTransport;
+use std::collections::HashMap;
+
+/// Registers hold whatever was last written to them. The output arm flags are cleared after they
+/// have been read once, as if the shield sent the frame right away, unless `stalled` is set.
+/// `poll_delay` only counts how often it was called.
+#[derive(Default)]
+pub struct FakeShield {
+    pub registers: HashMap<[u8; 2], Vec<u8>>,
+    pub writes: Vec<([u8; 2], Vec<u8>)>,
+    pub stalled: bool,
+    pub delays: usize,
+}
+
+impl FakeShield {
+    pub fn with_registers(registers: &[(&registers::Register, &[u8])]) -> Self {
+        Self {
+            registers: registers
+                .iter()
+                .map(|(register, value)| (register.address, value.to_vec()))
+                .collect(),
+            ..Self::default()
+        }
+    }
+}
+
+impl I2cTransport for FakeShield {
+    type Error = ();
+
+    fn write(&mut self, addr: &[u8; 2], bytes: &[u8]) -> Result<(), Self::Error> {
+        self.writes.push((*addr, bytes.to_vec()));
+        self.registers.insert(*addr, bytes.to_vec());
+        Ok(())
+    }
+
+    fn read(&mut self, addr: &[u8; 2], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        buffer.fill(0);
+        if let Some(value) = self.registers.get(addr) {
+            let len = value.len().min(buffer.len());
+            buffer[..len].copy_from_slice(&value[..len]);
+        }
+        if !self.stalled
+            && [
+                registers::OUTPUT_CONTROL_ARM.address,
+                registers::OUTPUT_TALLY_ARM.address,
+            ]
+            .contains(addr)
+        {
+            self.registers.remove(addr);
+        }
+        Ok(())
+    }
+
+    fn poll_delay(&mut self) {
+        self.delays += 1;
+    }
+}
@@ -1,5 +1,10 @@
 pub mod errors;
+#[cfg(test)]
+mod fake_i2c;
 pub mod registers;
 pub mod shield;
 pub mod traits;
 pub mod transport_adapters;
+
+#[cfg(feature = "eldritchwire")]
+mod wire;
@@ -1,3 +1,6 @@
+use bitflags::bitflags;
+use std::{fmt, ops::RangeInclusive};
+
 #[allow(dead_code)]
 pub struct Register {
     pub address: [u8; 2],
@@ -19,11 +22,67 @@ pub const FIRMWARE_VERSION: &'_ Register = &Register {
     length: 2,
 };
 
+pub const PROTOCOL_VERSION: &'_ Register = &Register {
+    address: [0x08, 0x00],
+    length: 2,
+};
+
+/// `IDENTITY` of every 3G-SDI Shield.
+pub const SHIELD_IDENTITY: [u8; 4] = *b"SDIC";
+
+/// Hardware revisions `EldritchShield::verify_device` accepts.
+pub const SUPPORTED_HARDWARE: RangeInclusive<Version> =
+    Version::new(1, 0)..=Version::new(1, u8::MAX);
+
+/// Firmware `EldritchShield::verify_device` accepts.
+pub const SUPPORTED_FIRMWARE: RangeInclusive<Version> =
+    Version::new(1, 0)..=Version::new(1, u8::MAX);
+
+/// Contents of the version registers, the minor version is the first byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl Version {
+    pub const fn new(major: u8, minor: u8) -> Self {
+        Self { major, minor }
+    }
+
+    pub const fn from_register(bytes: [u8; 2]) -> Self {
+        Self::new(bytes[1], bytes[0])
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
 pub const CONTROL: &'_ Register = &Register {
     address: [0x00, 0x10],
     length: 1,
 };
 
+bitflags! {
+    /// Bits of the `CONTROL` register. Bits without a name are reserved and kept as read.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct ControlRegister: u8 {
+        /// Replace the camera control data of the SDI output with the output control buffer.
+        const OVERRIDE_CONTROL = 1 << 0;
+        /// Replace the tally data of the SDI output with the output tally buffer.
+        const OVERRIDE_TALLY = 1 << 1;
+        /// Clear the incoming tally data, the shield resets this bit once done.
+        const RESET_TALLY = 1 << 2;
+        /// Generate the SDI output from the shield instead of passing the input through.
+        const OVERRIDE_OUTPUT = 1 << 3;
+
+        const _ = !0;
+    }
+}
+
 pub const OUTPUT_CONTROL_ARM: &'_ Register = &Register {
     address: [0x00, 0x20],
     length: 1,
@@ -83,3 +142,69 @@ pub const INCOMING_TALLY_DATA: &'_ Register = &Register {
     address: [0x00, 0x51],
     length: 0xfe,
 };
+
+pub const VIDEO_STATUS: &'_ Register = &Register {
+    address: [0x00, 0x60],
+    length: 1,
+};
+
+pub const INPUT_VIDEO_FORMAT: &'_ Register = &Register {
+    address: [0x01, 0x60],
+    length: 5,
+};
+
+pub const OUTPUT_VIDEO_FORMAT: &'_ Register = &Register {
+    address: [0x00, 0x61],
+    length: 5,
+};
+
+bitflags! {
+    /// Bits of the `VIDEO_STATUS` register.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct VideoStatus: u8 {
+        /// The shield has locked to a signal on its SDI input.
+        const INPUT_LOCKED = 1 << 0;
+        /// The SDI output is running, either passed through or generated by the shield.
+        const OUTPUT_LOCKED = 1 << 1;
+
+        const _ = !0;
+    }
+}
+
+/// Contents of the video format registers, laid out like the data of the video mode command
+/// (1.0) of the camera control protocol.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VideoFormat {
+    /// Frame rate in frames per second, e.g. 24, 25, 30, 50 or 60.
+    pub frame_rate: u8,
+    /// 0 for integer frame rates, 1 for the fractional M rates like 23.98.
+    pub m_rate: u8,
+    /// 0 NTSC, 1 PAL, 2 720, 3 1080, 4 2kDCI, 5 2k16:9, 6 UHD, 7 3k Anamorphic, 8 4k DCI.
+    pub dimensions: u8,
+    /// 0 progressive, 1 interlaced.
+    pub interlaced: u8,
+    /// 0 YUV.
+    pub color_space: u8,
+}
+
+impl VideoFormat {
+    pub const fn from_register(bytes: [u8; 5]) -> Self {
+        Self {
+            frame_rate: bytes[0],
+            m_rate: bytes[1],
+            dimensions: bytes[2],
+            interlaced: bytes[3],
+            color_space: bytes[4],
+        }
+    }
+
+    pub const fn to_register(self) -> [u8; 5] {
+        [
+            self.frame_rate,
+            self.m_rate,
+            self.dimensions,
+            self.interlaced,
+            self.color_space,
+        ]
+    }
+}
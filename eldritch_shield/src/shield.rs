@@ -1,10 +1,10 @@
 use crate::errors::ShieldError;
-use crate::registers;
+use crate::registers::{self, ControlRegister, Version, VideoFormat, VideoStatus};
 use crate::traits::I2cTransport;
 
 #[allow(dead_code)]
 pub struct EldritchShield<I2C> {
-    i2c: I2C,
+    pub(crate) i2c: I2C,
     address: u8,
 }
 
@@ -26,17 +26,45 @@ where
         String::from_utf8(buff.to_vec()).map_err(|_| ShieldError::InvalidResponse)
     }
 
-    pub fn get_hardware_version(&mut self) -> Result<(u8, u8), ShieldError<E>> {
+    pub fn get_hardware_version(&mut self) -> Result<Version, ShieldError<E>> {
         let mut buff = [0u8; registers::HARDWARE_VERSION.length];
-        self.i2c.read(&registers::IDENTITY.address, &mut buff)?;
-        Ok((buff[0], buff[1]))
+        self.i2c
+            .read(&registers::HARDWARE_VERSION.address, &mut buff)?;
+        Ok(Version::from_register(buff))
     }
 
-    pub fn get_firmware_version(&mut self) -> Result<(u8, u8), ShieldError<E>> {
+    pub fn get_firmware_version(&mut self) -> Result<Version, ShieldError<E>> {
         let mut buff = [0u8; registers::FIRMWARE_VERSION.length];
         self.i2c
             .read(&registers::FIRMWARE_VERSION.address, &mut buff)?;
-        Ok((buff[0], buff[1]))
+        Ok(Version::from_register(buff))
+    }
+
+    pub fn get_protocol_version(&mut self) -> Result<Version, ShieldError<E>> {
+        let mut buff = [0u8; registers::PROTOCOL_VERSION.length];
+        self.i2c
+            .read(&registers::PROTOCOL_VERSION.address, &mut buff)?;
+        Ok(Version::from_register(buff))
+    }
+
+    /// Checks that the device is a 3G-SDI Shield with hardware and firmware in
+    /// `SUPPORTED_HARDWARE` and `SUPPORTED_FIRMWARE`.
+    pub fn verify_device(&mut self) -> Result<(), ShieldError<E>> {
+        let mut identity = [0u8; registers::IDENTITY.length];
+        self.i2c.read(&registers::IDENTITY.address, &mut identity)?;
+        if identity != registers::SHIELD_IDENTITY {
+            return Err(ShieldError::UnexpectedIdentity(identity));
+        }
+
+        let hardware = self.get_hardware_version()?;
+        if !registers::SUPPORTED_HARDWARE.contains(&hardware) {
+            return Err(ShieldError::UnsupportedHardware(hardware));
+        }
+        let firmware = self.get_firmware_version()?;
+        if !registers::SUPPORTED_FIRMWARE.contains(&firmware) {
+            return Err(ShieldError::UnsupportedFirmware(firmware));
+        }
+        Ok(())
     }
 
     pub fn get_system_control_register(&mut self) -> Result<u8, ShieldError<E>> {
@@ -50,47 +78,92 @@ where
         Ok(())
     }
 
+    pub fn get_system_control(&mut self) -> Result<ControlRegister, ShieldError<E>> {
+        self.get_system_control_register()
+            .map(ControlRegister::from_bits_retain)
+    }
+
+    pub fn set_system_control(&mut self, control: ControlRegister) -> Result<(), ShieldError<E>> {
+        self.set_system_control_register(control.bits())
+    }
+
+    /// Reads the control register, lets `f` change any number of bits and writes it back once.
+    /// `ControlRegister::RESET_TALLY` is cleared from the value read, so the reset is only
+    /// triggered when `f` inserts it.
+    ///
+    /// ```
+    /// # use eldritch_shield::{errors::ShieldError, registers::ControlRegister, shield::EldritchShield, traits::I2cTransport};
+    /// # fn override_control<I2C: I2cTransport<Error = E>, E>(shield: &mut EldritchShield<I2C>) -> Result<(), ShieldError<E>> {
+    /// shield.modify(|reg| {
+    ///     reg.insert(ControlRegister::OVERRIDE_CONTROL | ControlRegister::OVERRIDE_TALLY);
+    ///     reg.remove(ControlRegister::OVERRIDE_OUTPUT);
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn modify<F>(&mut self, f: F) -> Result<(), ShieldError<E>>
+    where
+        F: FnOnce(&mut ControlRegister),
+    {
+        let mut control = self.get_system_control()?;
+        control.remove(ControlRegister::RESET_TALLY);
+        f(&mut control);
+        self.set_system_control(control)
+    }
+
     pub fn set_system_control_override(&mut self, enabled: bool) -> Result<(), ShieldError<E>> {
-        let mut buff = [0u8; registers::CONTROL.length];
-        self.i2c.read(&registers::CONTROL.address, &mut buff)?;
-        buff = if enabled {
-            [buff[0] | 0b0000_0001]
-        } else {
-            [buff[0] & 0b0000_1110]
-        };
-        self.i2c.write(&registers::CONTROL.address, &buff)?;
-        Ok(())
+        self.modify(|reg| reg.set(ControlRegister::OVERRIDE_CONTROL, enabled))
     }
 
     pub fn set_system_tally_override(&mut self, enabled: bool) -> Result<(), ShieldError<E>> {
-        let mut buff = [0u8; registers::CONTROL.length];
-        self.i2c.read(&registers::CONTROL.address, &mut buff)?;
-        buff = if enabled {
-            [buff[0] | 0b0000_0010]
-        } else {
-            [buff[0] & 0b0000_1101]
-        };
-        self.i2c.write(&registers::CONTROL.address, &buff)?;
-        Ok(())
+        self.modify(|reg| reg.set(ControlRegister::OVERRIDE_TALLY, enabled))
     }
 
     pub fn system_reset_tally(&mut self) -> Result<(), ShieldError<E>> {
-        let mut buff = [0u8; registers::CONTROL.length];
-        self.i2c.read(&registers::CONTROL.address, &mut buff)?;
-        buff = [buff[0] | 0b0000_0100];
-        self.i2c.write(&registers::CONTROL.address, &buff)?;
-        Ok(())
+        self.modify(|reg| reg.insert(ControlRegister::RESET_TALLY))
     }
 
     pub fn set_system_output_override(&mut self, enabled: bool) -> Result<(), ShieldError<E>> {
-        let mut buff = [0u8; registers::CONTROL.length];
-        self.i2c.read(&registers::CONTROL.address, &mut buff)?;
-        buff = if enabled {
-            [buff[0] | 0b0000_1000]
-        } else {
-            [buff[0] & 0b0000_0111]
-        };
-        self.i2c.write(&registers::CONTROL.address, &buff)?;
+        self.modify(|reg| reg.set(ControlRegister::OVERRIDE_OUTPUT, enabled))
+    }
+
+    pub fn get_video_status(&mut self) -> Result<VideoStatus, ShieldError<E>> {
+        let mut buff = [0u8; registers::VIDEO_STATUS.length];
+        self.i2c.read(&registers::VIDEO_STATUS.address, &mut buff)?;
+        Ok(VideoStatus::from_bits_retain(buff[0]))
+    }
+
+    pub fn is_sdi_input_locked(&mut self) -> Result<bool, ShieldError<E>> {
+        Ok(self.get_video_status()?.contains(VideoStatus::INPUT_LOCKED))
+    }
+
+    pub fn is_sdi_output_locked(&mut self) -> Result<bool, ShieldError<E>> {
+        Ok(self
+            .get_video_status()?
+            .contains(VideoStatus::OUTPUT_LOCKED))
+    }
+
+    /// Format of the signal on the SDI input, only meaningful while `is_sdi_input_locked`.
+    pub fn input_video_format(&mut self) -> Result<VideoFormat, ShieldError<E>> {
+        let mut buff = [0u8; registers::INPUT_VIDEO_FORMAT.length];
+        self.i2c
+            .read(&registers::INPUT_VIDEO_FORMAT.address, &mut buff)?;
+        Ok(VideoFormat::from_register(buff))
+    }
+
+    pub fn output_video_format(&mut self) -> Result<VideoFormat, ShieldError<E>> {
+        let mut buff = [0u8; registers::OUTPUT_VIDEO_FORMAT.length];
+        self.i2c
+            .read(&registers::OUTPUT_VIDEO_FORMAT.address, &mut buff)?;
+        Ok(VideoFormat::from_register(buff))
+    }
+
+    /// Format the shield generates while `ControlRegister::OVERRIDE_OUTPUT` is set.
+    pub fn set_output_video_format(&mut self, format: VideoFormat) -> Result<(), ShieldError<E>> {
+        self.i2c.write(
+            &registers::OUTPUT_VIDEO_FORMAT.address,
+            &format.to_register(),
+        )?;
         Ok(())
     }
 
@@ -214,3 +287,181 @@ fn create_buffer(size: u8) -> Result<Box<[u8]>, alloc::LayoutError> {
         Ok(Box::from_raw(slice))
     }
 }
+
+#[cfg(test)]
+mod shield_test {
+    use super::*;
+    use crate::fake_i2c::FakeShield;
+
+    #[test]
+    fn get_system_control() {
+        let mut shield = EldritchShield::with_i2c(FakeShield::with_registers(&[(
+            registers::CONTROL,
+            &[0b1000_1010],
+        )]));
+        let control = shield.get_system_control().unwrap();
+
+        assert!(
+            control.contains(ControlRegister::OVERRIDE_TALLY | ControlRegister::OVERRIDE_OUTPUT)
+        );
+        assert!(!control.contains(ControlRegister::OVERRIDE_CONTROL));
+        assert_eq!(control.bits(), 0b1000_1010);
+    }
+
+    #[test]
+    fn modify_writes_once() {
+        let mut shield = EldritchShield::with_i2c(FakeShield::with_registers(&[(
+            registers::CONTROL,
+            &[0b0000_1000],
+        )]));
+        shield
+            .modify(|reg| {
+                reg.insert(ControlRegister::OVERRIDE_CONTROL | ControlRegister::OVERRIDE_TALLY);
+                reg.remove(ControlRegister::OVERRIDE_OUTPUT);
+            })
+            .unwrap();
+
+        assert_eq!(
+            shield.i2c.writes,
+            vec![(registers::CONTROL.address, vec![0b0000_0011])]
+        );
+    }
+
+    #[test]
+    fn setters_keep_reserved_bits() {
+        let mut shield = EldritchShield::with_i2c(FakeShield::with_registers(&[(
+            registers::CONTROL,
+            &[0b1010_0001],
+        )]));
+        shield.set_system_control_override(false).unwrap();
+        shield.set_system_tally_override(true).unwrap();
+        shield.system_reset_tally().unwrap();
+        shield.set_system_output_override(true).unwrap();
+
+        assert_eq!(
+            shield.i2c.writes,
+            vec![
+                (registers::CONTROL.address, vec![0b1010_0000]),
+                (registers::CONTROL.address, vec![0b1010_0010]),
+                (registers::CONTROL.address, vec![0b1010_0110]),
+                (registers::CONTROL.address, vec![0b1010_1010]),
+            ]
+        );
+    }
+
+    #[test]
+    fn modify_does_not_repeat_reset_tally() {
+        let mut shield = EldritchShield::with_i2c(FakeShield::with_registers(&[(
+            registers::CONTROL,
+            &[0b0000_0100],
+        )]));
+        shield.set_system_tally_override(true).unwrap();
+
+        assert_eq!(
+            shield.i2c.writes,
+            vec![(registers::CONTROL.address, vec![0b0000_0010])]
+        );
+    }
+
+    #[test]
+    fn verify_device() {
+        let identity = (registers::IDENTITY, &b"SDIC"[..]);
+        let hardware = (registers::HARDWARE_VERSION, &[0x02, 0x01][..]);
+        let firmware = (registers::FIRMWARE_VERSION, &[0x05, 0x01][..]);
+        let verify = |values: &[(&registers::Register, &[u8])]| {
+            EldritchShield::with_i2c(FakeShield::with_registers(values)).verify_device()
+        };
+
+        let mut shield =
+            EldritchShield::with_i2c(FakeShield::with_registers(&[identity, hardware, firmware]));
+        assert_eq!(shield.verify_device(), Ok(()));
+        assert_eq!(shield.get_firmware_version(), Ok(Version::new(1, 5)));
+
+        assert_eq!(
+            verify(&[(registers::IDENTITY, b"ABCD"), hardware, firmware]),
+            Err(ShieldError::UnexpectedIdentity(*b"ABCD"))
+        );
+        assert_eq!(
+            verify(&[
+                identity,
+                (registers::HARDWARE_VERSION, &[0x00, 0x02]),
+                firmware
+            ]),
+            Err(ShieldError::UnsupportedHardware(Version::new(2, 0)))
+        );
+        assert_eq!(
+            verify(&[
+                identity,
+                hardware,
+                (registers::FIRMWARE_VERSION, &[0x09, 0x00])
+            ]),
+            Err(ShieldError::UnsupportedFirmware(Version::new(0, 9)))
+        );
+    }
+
+    #[test]
+    fn unexpected_identity_message() {
+        assert_eq!(
+            ShieldError::<std::io::Error>::UnexpectedIdentity([0x00; 4]).to_string(),
+            "Expected a 3G-SDI Shield identifying as \"SDIC\", found \"\\0\\0\\0\\0\""
+        );
+    }
+
+    #[test]
+    fn get_protocol_version() {
+        let mut shield = EldritchShield::with_i2c(FakeShield::with_registers(&[(
+            registers::PROTOCOL_VERSION,
+            &[0x03, 0x01],
+        )]));
+        assert_eq!(shield.get_protocol_version(), Ok(Version::new(1, 3)));
+    }
+
+    #[test]
+    fn video_status() {
+        let mut shield = EldritchShield::with_i2c(FakeShield::with_registers(&[(
+            registers::VIDEO_STATUS,
+            &[0b1000_0010],
+        )]));
+
+        assert_eq!(
+            shield.get_video_status().map(|status| status.bits()),
+            Ok(0b1000_0010)
+        );
+        assert_eq!(shield.is_sdi_input_locked(), Ok(false));
+        assert_eq!(shield.is_sdi_output_locked(), Ok(true));
+    }
+
+    #[test]
+    fn video_status_and_format() {
+        let mut shield = EldritchShield::with_i2c(FakeShield::with_registers(&[
+            (registers::VIDEO_STATUS, &[0b0000_0001]),
+            (registers::INPUT_VIDEO_FORMAT, &[24, 1, 3, 0, 0]),
+        ]));
+
+        assert_eq!(shield.is_sdi_input_locked(), Ok(true));
+        assert_eq!(shield.is_sdi_output_locked(), Ok(false));
+        assert_eq!(
+            shield.input_video_format(),
+            Ok(VideoFormat {
+                frame_rate: 24,
+                m_rate: 1,
+                dimensions: 3,
+                interlaced: 0,
+                color_space: 0,
+            })
+        );
+
+        let format = VideoFormat {
+            frame_rate: 50,
+            dimensions: 3,
+            interlaced: 1,
+            ..VideoFormat::default()
+        };
+        shield.set_output_video_format(format).unwrap();
+        assert_eq!(
+            shield.i2c.writes,
+            vec![(registers::OUTPUT_VIDEO_FORMAT.address, vec![50, 0, 3, 1, 0])]
+        );
+        assert_eq!(shield.output_video_format(), Ok(format));
+    }
+}
@@ -1,6 +1,15 @@
+use std::time::Duration;
+
 pub trait I2cTransport {
     type Error;
 
     fn write(&mut self, addr: &[u8; 2], bytes: &[u8]) -> Result<(), Self::Error>;
     fn read(&mut self, addr: &[u8; 2], buffer: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Called between polls of an arm flag while waiting for the shield to send a frame. The shield
+    /// only sends once per video frame, so by default this sleeps for a millisecond rather than
+    /// hammering the bus. Transports with their own timer, or none at all, can override it.
+    fn poll_delay(&mut self) {
+        std::thread::sleep(Duration::from_millis(1));
+    }
 }
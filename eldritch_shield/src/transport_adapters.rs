@@ -3,3 +3,6 @@ mod rppal;
 
 #[cfg(feature = "mock-i2c")]
 pub mod mock_i2c;
+
+#[cfg(feature = "embedded-hal")]
+pub mod embedded_hal;
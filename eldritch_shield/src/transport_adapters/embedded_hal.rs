@@ -0,0 +1,109 @@
+use crate::shield::EldritchShield;
+use crate::traits::I2cTransport;
+use embedded_hal::i2c::I2c;
+
+/// Runs the shield over any `embedded-hal` 1.0 I2C bus. `I2cTransport` only deals in register
+/// addresses, so the adapter holds the shield's device address alongside the bus.
+pub struct HalI2c<I2C> {
+    i2c: I2C,
+    address: u8,
+}
+
+impl<I2C: I2c> HalI2c<I2C> {
+    pub fn new(i2c: I2C, address: u8) -> Self {
+        Self { i2c, address }
+    }
+
+    /// Gives the bus back, e.g. to share it with other devices.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+}
+
+impl<I2C: I2c> I2cTransport for HalI2c<I2C> {
+    type Error = I2C::Error;
+
+    fn write(&mut self, addr: &[u8; 2], bytes: &[u8]) -> Result<(), Self::Error> {
+        let mut buff: Vec<u8> = Vec::from(addr);
+        buff.extend_from_slice(bytes);
+        self.i2c.write(self.address, &buff)
+    }
+
+    fn read(&mut self, addr: &[u8; 2], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c.write_read(self.address, addr, buffer)
+    }
+}
+
+impl<I2C: I2c> EldritchShield<HalI2c<I2C>> {
+    /// Shorthand for `EldritchShield::new(HalI2c::new(i2c, address), address)`.
+    pub fn with_hal(i2c: I2C, address: u8) -> Self {
+        Self::new(HalI2c::new(i2c, address), address)
+    }
+}
+
+#[cfg(test)]
+mod embedded_hal_test {
+    use super::*;
+    use crate::registers;
+    use embedded_hal::i2c::{ErrorType, Operation};
+    use std::convert::Infallible;
+
+    /// Logs every operation as `(device address, bytes written or read)`, reads return `0x5a`.
+    #[derive(Default)]
+    struct FakeBus {
+        operations: Vec<(u8, &'static str, Vec<u8>)>,
+    }
+
+    impl ErrorType for FakeBus {
+        type Error = Infallible;
+    }
+
+    impl I2c for FakeBus {
+        fn transaction(
+            &mut self,
+            address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            for operation in operations {
+                match operation {
+                    Operation::Write(bytes) => {
+                        self.operations.push((address, "write", bytes.to_vec()))
+                    }
+                    Operation::Read(buffer) => {
+                        buffer.fill(0x5a);
+                        self.operations.push((address, "read", buffer.to_vec()))
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn write_prefixes_register_address() {
+        let mut shield = EldritchShield::with_hal(FakeBus::default(), 0x6e);
+        shield.set_output_control_data(&[0x01, 0x02]).unwrap();
+
+        assert_eq!(
+            shield.i2c.release().operations,
+            vec![(0x6e, "write", vec![0x00, 0x21, 0x01, 0x02])]
+        );
+    }
+
+    #[test]
+    fn read_writes_register_address_then_reads() {
+        let mut i2c = HalI2c::new(FakeBus::default(), 0x6f);
+        let mut buffer = [0u8; registers::HARDWARE_VERSION.length];
+        i2c.read(&registers::HARDWARE_VERSION.address, &mut buffer)
+            .unwrap();
+
+        assert_eq!(buffer, [0x5a, 0x5a]);
+        assert_eq!(
+            i2c.release().operations,
+            vec![
+                (0x6f, "write", vec![0x04, 0x00]),
+                (0x6f, "read", vec![0x5a, 0x5a]),
+            ]
+        );
+    }
+}
@@ -119,6 +119,7 @@ mod test {
     use std::path::PathBuf;
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn test_mock_i2c_from_file() {
         // Path to your test data file (adjust if needed)
         let path = PathBuf::from("src/transport_adapters/test_data.txt");
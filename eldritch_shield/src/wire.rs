@@ -0,0 +1,276 @@
+use crate::errors::ShieldError;
+use crate::registers;
+use crate::shield::EldritchShield;
+use crate::traits::I2cTransport;
+use eldritchwire::{
+    encode_frame_packets, parse_frame_packet_lenient, AddressedCommand, ParsedFrame, TallyFrame,
+};
+
+/// How many times an output arm flag is polled before giving up with `ShieldError::Busy`, with
+/// `I2cTransport::poll_delay` between polls. The shield sends a queued frame with the next video
+/// frame, at the default delay of a millisecond this leaves about a second for that.
+const ARM_POLL_LIMIT: usize = 1000;
+
+impl<I2C, E> EldritchShield<I2C>
+where
+    I2C: I2cTransport<Error = E>,
+{
+    /// Encodes `commands` into as few frame packets as they fit in and sends them one after the
+    /// other. Before each packet this waits for the shield to clear the output arm flag, so a frame
+    /// that is still queued is never overwritten. If the flag doesn't clear this fails with
+    /// `ShieldError::Busy`, packets sent up to that point stay sent.
+    pub fn send_commands(&mut self, commands: &[AddressedCommand]) -> Result<(), ShieldError<E>> {
+        let packets = encode_frame_packets(commands).map_err(ShieldError::Wire)?;
+        for packet in packets {
+            if packet.len() > registers::OUTPUT_CONTROL_DATA.length {
+                return Err(ShieldError::OutOfRange);
+            }
+            self.wait_until_sent(Self::is_output_control_armed)?;
+            self.set_output_control_length(packet.len() as u8)?;
+            self.set_output_control_data(&packet)?;
+            self.output_control_arm()?;
+        }
+        Ok(())
+    }
+
+    /// Returns `None` while the shield is still waiting for an incoming frame. Otherwise the frame
+    /// is read and the incoming buffer re-armed before it is parsed, so a malformed frame never
+    /// stalls the next one. A bad command doesn't cost the rest of the frame, it is reported in
+    /// `ParsedFrame::errors` next to the commands that did parse. Only a frame too large to parse
+    /// at all is reported as `ShieldError::Wire`.
+    pub fn receive_commands(&mut self) -> Result<Option<ParsedFrame>, ShieldError<E>> {
+        if self.is_incoming_control_armed()? {
+            return Ok(None);
+        }
+        let packet = match self.get_incoming_control_length()? {
+            0 => Vec::new(),
+            _ => self.get_incoming_control_data()?.into_vec(),
+        };
+        self.incoming_control_arm()?;
+
+        parse_frame_packet_lenient(packet)
+            .map(Some)
+            .map_err(ShieldError::Wire)
+    }
+
+    /// Waits for the previous tally frame to be consumed, then writes and arms `tally`. Fails with
+    /// `ShieldError::Busy` if the shield never clears the output tally arm flag.
+    pub fn send_tally(&mut self, tally: &TallyFrame) -> Result<(), ShieldError<E>> {
+        let data = tally.as_bytes();
+        if data.len() > registers::OUTPUT_TALLY_DATA.length {
+            return Err(ShieldError::OutOfRange);
+        }
+        self.wait_until_sent(Self::output_tally_armed)?;
+        self.set_output_tally_length(data.len() as u8)?;
+        self.set_output_tally_data(data)?;
+        self.output_tally_arm()?;
+        Ok(())
+    }
+
+    /// The last tally frame the shield received, tally is state rather than an event so this reads
+    /// the incoming buffer whether or not it changed and re-arms it.
+    pub fn receive_tally(&mut self) -> Result<TallyFrame, ShieldError<E>> {
+        let tally = match self.get_incoming_tally_length()? {
+            0 => TallyFrame::new(),
+            _ => TallyFrame::from_bytes(&self.get_incoming_tally_data()?),
+        };
+        self.incoming_tally_arm()?;
+        Ok(tally)
+    }
+
+    fn wait_until_sent(
+        &mut self,
+        mut armed: impl FnMut(&mut Self) -> Result<bool, ShieldError<E>>,
+    ) -> Result<(), ShieldError<E>> {
+        for attempt in 0..ARM_POLL_LIMIT {
+            if attempt > 0 {
+                self.i2c.poll_delay();
+            }
+            if !armed(self)? {
+                return Ok(());
+            }
+        }
+        Err(ShieldError::Busy)
+    }
+}
+
+#[cfg(test)]
+mod wire_test {
+    use super::*;
+    use crate::fake_i2c::FakeShield;
+    use eldritchwire::{
+        commands::{lens_commands::LensCommand, Command},
+        CommandParseError, EldritchError, ErrorContext, FixedPointDecimal, Operation,
+    };
+
+    fn focus(device_id: u8) -> AddressedCommand {
+        AddressedCommand {
+            device_id,
+            command: Command::Lens(LensCommand::Focus {
+                operation: Operation::Assign,
+                data: FixedPointDecimal::from_real_val(0.5).unwrap(),
+            }),
+        }
+    }
+
+    fn incoming(packet: &[u8]) -> EldritchShield<FakeShield> {
+        EldritchShield::with_i2c(FakeShield::with_registers(&[
+            (registers::INCOMING_CONTROL_ARM, &[0x00]),
+            (registers::INCOMING_CONTROL_LENGTH, &[packet.len() as u8]),
+            (registers::INCOMING_CONTROL_DATA, packet),
+        ]))
+    }
+
+    #[test]
+    fn send_commands_writes_length_data_then_arms() {
+        let mut shield = EldritchShield::with_i2c(FakeShield::default());
+        shield.send_commands(&[focus(1)]).unwrap();
+
+        assert_eq!(
+            shield.i2c.writes,
+            vec![
+                (registers::OUTPUT_CONTROL_LENGTH.address, vec![12]),
+                (
+                    registers::OUTPUT_CONTROL_DATA.address,
+                    vec![0x01, 0x06, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x04, 0x00, 0x00]
+                ),
+                (registers::OUTPUT_CONTROL_ARM.address, vec![0x01]),
+            ]
+        );
+    }
+
+    #[test]
+    fn send_commands_splits_packets() {
+        let mut shield = EldritchShield::with_i2c(FakeShield::default());
+        shield.send_commands(&vec![focus(1); 22]).unwrap();
+
+        let arms = shield
+            .i2c
+            .writes
+            .iter()
+            .filter(|(addr, _)| *addr == registers::OUTPUT_CONTROL_ARM.address)
+            .count();
+        assert_eq!(arms, 2);
+    }
+
+    #[test]
+    fn send_commands_gives_up_when_busy() {
+        let mut shield = EldritchShield::with_i2c(FakeShield {
+            stalled: true,
+            ..FakeShield::with_registers(&[(registers::OUTPUT_CONTROL_ARM, &[0x01])])
+        });
+
+        assert_eq!(shield.send_commands(&[focus(1)]), Err(ShieldError::Busy));
+        assert!(shield.i2c.writes.is_empty());
+        assert_eq!(shield.i2c.delays, ARM_POLL_LIMIT - 1);
+    }
+
+    #[test]
+    fn send_commands_waits_between_polls() {
+        let mut shield = EldritchShield::with_i2c(FakeShield::with_registers(&[(
+            registers::OUTPUT_CONTROL_ARM,
+            &[0x01],
+        )]));
+        shield.send_commands(&[focus(1)]).unwrap();
+
+        assert_eq!(shield.i2c.delays, 1);
+    }
+
+    #[test]
+    fn receive_commands_parses_and_rearms() {
+        let mut shield = incoming(&[
+            0x01, 0x06, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x04, 0x00, 0x00,
+        ]);
+
+        assert_eq!(
+            shield.receive_commands(),
+            Ok(Some(ParsedFrame {
+                commands: vec![focus(1)],
+                errors: vec![],
+            }))
+        );
+        assert_eq!(shield.receive_commands(), Ok(None));
+    }
+
+    #[test]
+    fn receive_commands_keeps_good_commands_next_to_bad_ones() {
+        let mut shield = incoming(&[
+            0x02, 0x04, 0x00, 0x00, 0x00, 0x7f, 0x00, 0x00, // Unknown lens parameter
+            0x01, 0x06, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x04, 0x00, 0x00,
+        ]);
+
+        assert_eq!(
+            shield.receive_commands(),
+            Ok(Some(ParsedFrame {
+                commands: vec![focus(1)],
+                errors: vec![CommandParseError {
+                    context: ErrorContext {
+                        offset: 0,
+                        index: 5,
+                        device_id: 2,
+                        category: Some(0x00),
+                        parameter: Some(0x7f),
+                    },
+                    error: EldritchError::UnknownParameter {
+                        category: 0x00,
+                        parameter: 0x7f,
+                        raw: vec![0x00, 0x7f, 0x00, 0x00],
+                    },
+                }],
+            }))
+        );
+        assert_eq!(shield.receive_commands(), Ok(None));
+    }
+
+    #[test]
+    fn send_tally_writes_length_data_then_arms() {
+        let mut shield = EldritchShield::with_i2c(FakeShield::default());
+        let mut tally = TallyFrame::new();
+        tally.set_program(1, true);
+        tally.set_preview(3, true);
+        shield.send_tally(&tally).unwrap();
+
+        assert_eq!(
+            shield.i2c.writes,
+            vec![
+                (registers::OUTPUT_TALLY_LENGTH.address, vec![3]),
+                (registers::OUTPUT_TALLY_DATA.address, vec![0x01, 0x00, 0x02]),
+                (registers::OUTPUT_TALLY_ARM.address, vec![0x01]),
+            ]
+        );
+    }
+
+    #[test]
+    fn send_tally_gives_up_when_busy() {
+        let mut shield = EldritchShield::with_i2c(FakeShield {
+            stalled: true,
+            ..FakeShield::with_registers(&[(registers::OUTPUT_TALLY_ARM, &[0x01])])
+        });
+
+        assert_eq!(
+            shield.send_tally(&TallyFrame::from_bytes(&[0x01])),
+            Err(ShieldError::Busy)
+        );
+        assert!(shield.i2c.writes.is_empty());
+        assert_eq!(shield.i2c.delays, ARM_POLL_LIMIT - 1);
+    }
+
+    #[test]
+    fn receive_tally() {
+        let mut shield = EldritchShield::with_i2c(FakeShield::with_registers(&[
+            (registers::INCOMING_TALLY_LENGTH, &[2]),
+            (registers::INCOMING_TALLY_DATA, &[0x02, 0x01]),
+        ]));
+        let tally = shield.receive_tally().unwrap();
+
+        assert!(tally.preview(1) && tally.program(2));
+        assert_eq!(
+            shield.i2c.writes,
+            vec![(registers::INCOMING_TALLY_ARM.address, vec![0x01])]
+        );
+        assert_eq!(
+            EldritchShield::with_i2c(FakeShield::default()).receive_tally(),
+            Ok(TallyFrame::new())
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use crate::{commands::Command, AddressedCommand, CameraRegistry, BROADCAST_DEVICE_ID};
+use alloc::{boxed::Box, vec, vec::Vec};
+
+/// Identifies a parameter by its command category and parameter id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParameterId {
+    pub category: u8,
+    pub parameter: u8,
+}
+
+/// A parameter of a camera that took on a new value, `old` and `new` are `Assign` commands.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateChange {
+    pub device_id: u8,
+    pub parameter: ParameterId,
+    pub old: Option<Command>,
+    pub new: Command,
+}
+
+/// Selects which [`StateChange`]s a subscriber receives, an empty filter matches everything.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChangeFilter {
+    device_id: Option<u8>,
+    category: Option<u8>,
+}
+
+impl ChangeFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn device_id(mut self, device_id: u8) -> Self {
+        self.device_id = Some(device_id);
+        self
+    }
+
+    pub fn category(mut self, category: u8) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    pub fn matches(&self, change: &StateChange) -> bool {
+        self.device_id.is_none_or(|id| id == change.device_id)
+            && self
+                .category
+                .is_none_or(|category| category == change.parameter.category)
+    }
+}
+
+type Subscriber = Box<dyn FnMut(&StateChange)>;
+
+/// Keeps a [`CameraRegistry`] up to date and reports only the parameters whose value changed,
+/// so a camera re-sending its full state doesn't produce any notifications.
+#[derive(Default)]
+pub struct ChangeNotifier {
+    registry: CameraRegistry,
+    subscribers: Vec<(ChangeFilter, Subscriber)>,
+}
+
+impl ChangeNotifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn registry(&self) -> &CameraRegistry {
+        &self.registry
+    }
+
+    /// Calls `callback` for every change matching `filter`. Use a channel sender in the callback
+    /// to hand changes off to another thread.
+    pub fn subscribe(
+        &mut self,
+        filter: ChangeFilter,
+        callback: impl FnMut(&StateChange) + 'static,
+    ) {
+        self.subscribers.push((filter, Box::new(callback)));
+    }
+
+    /// Applies a command, notifies subscribers and returns every change it caused.
+    pub fn apply(&mut self, command: &AddressedCommand) -> Vec<StateChange> {
+        let parameter = ParameterId {
+            category: command.command.category(),
+            parameter: command.command.parameter(),
+        };
+        let device_ids = if command.device_id == BROADCAST_DEVICE_ID {
+            self.registry
+                .iter()
+                .map(|(device_id, _)| device_id)
+                .collect()
+        } else {
+            vec![command.device_id]
+        };
+
+        let mut changes = Vec::new();
+        for device_id in device_ids {
+            let camera = self.registry.register(device_id);
+            let old = camera.value(parameter.category, parameter.parameter);
+            camera.apply(&command.command);
+            let new = camera.value(parameter.category, parameter.parameter);
+
+            if let Some(new) = new.filter(|new| old.as_ref() != Some(new)) {
+                changes.push(StateChange {
+                    device_id,
+                    parameter,
+                    old,
+                    new,
+                });
+            }
+        }
+
+        for change in &changes {
+            self.subscribers
+                .iter_mut()
+                .filter(|(filter, _)| filter.matches(change))
+                .for_each(|(_, callback)| callback(change));
+        }
+        changes
+    }
+
+    pub fn apply_all<'a>(
+        &mut self,
+        commands: impl IntoIterator<Item = &'a AddressedCommand>,
+    ) -> Vec<StateChange> {
+        commands
+            .into_iter()
+            .flat_map(|command| self.apply(command))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod changes_test {
+    use super::*;
+    use crate::{
+        commands::{lens_commands::LensCommand, video_commands::VideoCommand},
+        FixedPointDecimal, Operation,
+    };
+    use std::{cell::RefCell, rc::Rc};
+
+    fn focus(device_id: u8, operation: Operation, raw_val: i16) -> AddressedCommand {
+        AddressedCommand {
+            device_id,
+            command: Command::Lens(LensCommand::Focus {
+                operation,
+                data: FixedPointDecimal { raw_val },
+            }),
+        }
+    }
+
+    fn iso(device_id: u8, data: i32) -> AddressedCommand {
+        AddressedCommand {
+            device_id,
+            command: Command::Video(VideoCommand::ISO {
+                operation: Operation::Assign,
+                data,
+            }),
+        }
+    }
+
+    #[test]
+    fn first_value_is_a_change() {
+        let mut notifier = ChangeNotifier::new();
+        let changes = notifier.apply(&focus(4, Operation::Assign, 0x0400));
+
+        assert_eq!(
+            changes,
+            vec![StateChange {
+                device_id: 4,
+                parameter: ParameterId {
+                    category: 0x00,
+                    parameter: 0x00
+                },
+                old: None,
+                new: focus(4, Operation::Assign, 0x0400).command,
+            }]
+        );
+    }
+
+    #[test]
+    fn repeated_value_is_not_a_change() {
+        let mut notifier = ChangeNotifier::new();
+        let frame = [focus(4, Operation::Assign, 0x0400), iso(4, 800)];
+
+        assert_eq!(notifier.apply_all(&frame).len(), 2);
+        assert_eq!(notifier.apply_all(&frame), vec![]);
+    }
+
+    #[test]
+    fn increment_reports_resulting_value() {
+        let mut notifier = ChangeNotifier::new();
+        notifier.apply(&focus(4, Operation::Assign, 0x0400));
+        let changes = notifier.apply(&focus(4, Operation::Increment, 0x0100));
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            changes[0].old,
+            Some(focus(4, Operation::Assign, 0x0400).command)
+        );
+        assert_eq!(changes[0].new, focus(4, Operation::Assign, 0x0500).command);
+    }
+
+    #[test]
+    fn broadcast_reports_change_per_camera() {
+        let mut notifier = ChangeNotifier::new();
+        notifier.apply(&iso(1, 800));
+        notifier.apply(&iso(2, 400));
+        let changes = notifier.apply(&iso(BROADCAST_DEVICE_ID, 800));
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].device_id, 2);
+    }
+
+    #[test]
+    fn subscribers_are_filtered() {
+        let mut notifier = ChangeNotifier::new();
+        let lens_changes = Rc::new(RefCell::new(Vec::new()));
+        let camera_two_changes = Rc::new(RefCell::new(Vec::new()));
+
+        let sink = Rc::clone(&lens_changes);
+        notifier.subscribe(
+            ChangeFilter::new().category(LensCommand::CATEGORY),
+            move |change| sink.borrow_mut().push(change.clone()),
+        );
+        let sink = Rc::clone(&camera_two_changes);
+        notifier.subscribe(ChangeFilter::new().device_id(2), move |change| {
+            sink.borrow_mut().push(change.clone())
+        });
+
+        notifier.apply_all(&[
+            focus(1, Operation::Assign, 0x0100),
+            iso(1, 800),
+            iso(2, 800),
+            focus(2, Operation::Assign, 0x0100),
+        ]);
+
+        assert_eq!(
+            lens_changes
+                .borrow()
+                .iter()
+                .map(|change| change.device_id)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(
+            camera_two_changes
+                .borrow()
+                .iter()
+                .map(|change| change.parameter.category)
+                .collect::<Vec<_>>(),
+            vec![0x01, 0x00]
+        );
+    }
+}
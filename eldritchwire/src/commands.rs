@@ -1,9 +1,63 @@
+/// Declares a `#[repr(i8)]` enum for a protocol code along with `TryFrom<i8>` and `From<_> for i8`.
+/// Codes that are not listed fail to convert with [`EldritchError::DataOutOfBounds`].
+macro_rules! protocol_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[repr(i8)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant = $value,)*
+        }
+
+        impl TryFrom<i8> for $name {
+            type Error = $crate::EldritchError;
+
+            fn try_from(value: i8) -> Result<Self, Self::Error> {
+                match value {
+                    $($value => Ok($name::$variant),)*
+                    _ => Err($crate::EldritchError::DataOutOfBounds),
+                }
+            }
+        }
+
+        impl From<$name> for i8 {
+            fn from(value: $name) -> Self {
+                value as i8
+            }
+        }
+    };
+}
+
+/// `From` conversions between a bitflag type and the integer it is sent as, unknown bits are kept.
+macro_rules! wire_flags {
+    ($name:ident, $bits:ty) => {
+        impl From<$bits> for $name {
+            fn from(bits: $bits) -> Self {
+                Self::from_bits_retain(bits)
+            }
+        }
+
+        impl From<$name> for $bits {
+            fn from(flags: $name) -> Self {
+                flags.bits()
+            }
+        }
+    };
+}
+
 pub mod audio_commands;
 pub mod color_correction_commands;
 pub mod configuration_commands;
 pub mod display_commands;
 pub mod lens_commands;
 pub mod media_commands;
+pub mod metadata_commands;
 pub mod output_commands;
 pub mod ptz_control_commands;
 pub mod reference_commands;
@@ -11,8 +65,10 @@ pub mod tally_commands;
 pub mod video_commands;
 
 use crate::error::EldritchError;
+use alloc::{vec, vec::Vec};
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Command {
     Lens(lens_commands::LensCommand),
     Video(video_commands::VideoCommand),
@@ -25,25 +81,33 @@ pub enum Command {
     ColorCorrection(color_correction_commands::ColorCorrectionCommand),
     Media(media_commands::MediaCommand),
     PtzControl(ptz_control_commands::PtzControlCommand),
+    Metadata(metadata_commands::MetadataCommand),
+    Unknown {
+        category: u8,
+        parameter: u8,
+        data_type: u8,
+        operation: u8,
+        payload: Vec<u8>,
+    },
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct CommandData<'a> {
     bytes: &'a [u8],
 }
 
 impl<'a> CommandData<'a> {
     pub fn new(bytes: &'a [u8]) -> Result<Self, EldritchError> {
-        if bytes.len() < 3 {
+        if bytes.len() < 4 {
             return Err(EldritchError::InvalidCommandData {
-                message: "Package to short".into(),
+                message: "Command data shorter than 4 bytes",
                 data: bytes.to_vec(),
             });
         }
         Ok(Self { bytes })
     }
 
-    pub fn raw(&self) -> &[u8] {
+    pub fn raw(&self) -> &'a [u8] {
         self.bytes
     }
 }
@@ -75,27 +139,196 @@ impl CommandData<'_> {
 }
 
 pub fn parse_command(cmd_buffer: &[u8]) -> Result<Command, EldritchError> {
-    if let Ok(cmd_data) = CommandData::new(cmd_buffer) {
-        let command = match cmd_data.category() {
-            0x00 => Command::Lens(lens_commands::parse_command(cmd_data)?),
-            0x01 => Command::Video(video_commands::parse_command(cmd_data)?),
-            0x02 => Command::Audio(audio_commands::parse_command(cmd_data)?),
-            0x03 => Command::Output(output_commands::parse_command(cmd_data)?),
-            0x04 => Command::Display(display_commands::parse_command(cmd_data)?),
-            0x05 => Command::Tally(tally_commands::parse_command(cmd_data)?),
-            0x06 => Command::Reference(reference_commands::parse_command(cmd_data)?),
-            0x07 => Command::Configuration(configuration_commands::parse_command(cmd_data)?),
-            0x08 => Command::ColorCorrection(color_correction_commands::parse_command(cmd_data)?),
-            0x0a => Command::Media(media_commands::parse_command(cmd_data)?),
-            0x0b => Command::PtzControl(ptz_control_commands::parse_command(cmd_data)?),
-            _ => todo!("Command category has either not been implemented or is invalid"),
-        };
-        Ok(command)
-    } else {
-        Err(EldritchError::InvalidCommandData {
-            message: "No matching command group".into(),
-            data: cmd_buffer.to_vec(),
-        })
+    let cmd_data = CommandData::new(cmd_buffer)?;
+    if !matches!(cmd_data.data_type(), 0x00..=0x05 | 0x80) {
+        return Err(EldritchError::UnknownDataType {
+            data_type: *cmd_data.data_type(),
+            raw: cmd_buffer.to_vec(),
+        });
+    }
+
+    let command = match cmd_data.category() {
+        0x00 => Command::Lens(lens_commands::parse_command(cmd_data)?),
+        0x01 => Command::Video(video_commands::parse_command(cmd_data)?),
+        0x02 => Command::Audio(audio_commands::parse_command(cmd_data)?),
+        0x03 => Command::Output(output_commands::parse_command(cmd_data)?),
+        0x04 => Command::Display(display_commands::parse_command(cmd_data)?),
+        0x05 => Command::Tally(tally_commands::parse_command(cmd_data)?),
+        0x06 => Command::Reference(reference_commands::parse_command(cmd_data)?),
+        0x07 => Command::Configuration(configuration_commands::parse_command(cmd_data)?),
+        0x08 => Command::ColorCorrection(color_correction_commands::parse_command(cmd_data)?),
+        0x0a => Command::Media(media_commands::parse_command(cmd_data)?),
+        0x0b => Command::PtzControl(ptz_control_commands::parse_command(cmd_data)?),
+        0x0c => Command::Metadata(metadata_commands::parse_command(cmd_data)?),
+        _ => {
+            return Err(EldritchError::UnknownCategory {
+                category: *cmd_data.category(),
+                raw: cmd_buffer.to_vec(),
+            })
+        }
+    };
+    Ok(command)
+}
+
+/// Parses a command the same way as [`parse_command`], but commands with an unknown category,
+/// parameter or data type are returned as [`Command::Unknown`] so they can still be forwarded.
+pub fn parse_command_or_unknown(cmd_buffer: &[u8]) -> Result<Command, EldritchError> {
+    match parse_command(cmd_buffer) {
+        Err(
+            EldritchError::UnknownCategory { .. }
+            | EldritchError::UnknownDataType { .. }
+            | EldritchError::UnknownParameter { .. },
+        ) => {
+            let cmd_data = CommandData::new(cmd_buffer)?;
+            Ok(Command::Unknown {
+                category: *cmd_data.category(),
+                parameter: *cmd_data.parameter(),
+                data_type: *cmd_data.data_type(),
+                operation: *cmd_data.operation(),
+                payload: cmd_data.data_buff().to_vec(),
+            })
+        }
+        result => result,
+    }
+}
+
+impl Command {
+    /// Text name and category of every command group, in category order.
+    pub const GROUPS: [(&'static str, u8); 12] = [
+        (
+            lens_commands::LensCommand::NAME,
+            lens_commands::LensCommand::CATEGORY,
+        ),
+        (
+            video_commands::VideoCommand::NAME,
+            video_commands::VideoCommand::CATEGORY,
+        ),
+        (
+            audio_commands::AudioCommand::NAME,
+            audio_commands::AudioCommand::CATEGORY,
+        ),
+        (
+            output_commands::OutputCommand::NAME,
+            output_commands::OutputCommand::CATEGORY,
+        ),
+        (
+            display_commands::DisplayCommand::NAME,
+            display_commands::DisplayCommand::CATEGORY,
+        ),
+        (
+            tally_commands::TallyCommand::NAME,
+            tally_commands::TallyCommand::CATEGORY,
+        ),
+        (
+            reference_commands::ReferenceCommand::NAME,
+            reference_commands::ReferenceCommand::CATEGORY,
+        ),
+        (
+            configuration_commands::ConfigurationCommand::NAME,
+            configuration_commands::ConfigurationCommand::CATEGORY,
+        ),
+        (
+            color_correction_commands::ColorCorrectionCommand::NAME,
+            color_correction_commands::ColorCorrectionCommand::CATEGORY,
+        ),
+        (
+            media_commands::MediaCommand::NAME,
+            media_commands::MediaCommand::CATEGORY,
+        ),
+        (
+            ptz_control_commands::PtzControlCommand::NAME,
+            ptz_control_commands::PtzControlCommand::CATEGORY,
+        ),
+        (
+            metadata_commands::MetadataCommand::NAME,
+            metadata_commands::MetadataCommand::CATEGORY,
+        ),
+    ];
+
+    pub fn category(&self) -> u8 {
+        match self {
+            Command::Lens(_) => lens_commands::LensCommand::CATEGORY,
+            Command::Video(_) => video_commands::VideoCommand::CATEGORY,
+            Command::Audio(_) => audio_commands::AudioCommand::CATEGORY,
+            Command::Output(_) => output_commands::OutputCommand::CATEGORY,
+            Command::Display(_) => display_commands::DisplayCommand::CATEGORY,
+            Command::Tally(_) => tally_commands::TallyCommand::CATEGORY,
+            Command::Reference(_) => reference_commands::ReferenceCommand::CATEGORY,
+            Command::Configuration(_) => configuration_commands::ConfigurationCommand::CATEGORY,
+            Command::ColorCorrection(_) => {
+                color_correction_commands::ColorCorrectionCommand::CATEGORY
+            }
+            Command::Media(_) => media_commands::MediaCommand::CATEGORY,
+            Command::PtzControl(_) => ptz_control_commands::PtzControlCommand::CATEGORY,
+            Command::Metadata(_) => metadata_commands::MetadataCommand::CATEGORY,
+            Command::Unknown { category, .. } => *category,
+        }
+    }
+
+    pub fn parameter(&self) -> u8 {
+        match self {
+            Command::Lens(cmd) => cmd.parameter(),
+            Command::Video(cmd) => cmd.parameter(),
+            Command::Audio(cmd) => cmd.parameter(),
+            Command::Output(cmd) => cmd.parameter(),
+            Command::Display(cmd) => cmd.parameter(),
+            Command::Tally(cmd) => cmd.parameter(),
+            Command::Reference(cmd) => cmd.parameter(),
+            Command::Configuration(cmd) => cmd.parameter(),
+            Command::ColorCorrection(cmd) => cmd.parameter(),
+            Command::Media(cmd) => cmd.parameter(),
+            Command::PtzControl(cmd) => cmd.parameter(),
+            Command::Metadata(cmd) => cmd.parameter(),
+            Command::Unknown { parameter, .. } => *parameter,
+        }
+    }
+}
+
+/// Fails with `EldritchError::DataOutOfBounds` for a string longer than its parameter allows.
+pub fn encode_command(command: &Command) -> Result<Vec<u8>, EldritchError> {
+    match command {
+        Command::Lens(cmd) => cmd.to_command_bytes(),
+        Command::Video(cmd) => cmd.to_command_bytes(),
+        Command::Audio(cmd) => cmd.to_command_bytes(),
+        Command::Output(cmd) => cmd.to_command_bytes(),
+        Command::Display(cmd) => cmd.to_command_bytes(),
+        Command::Tally(cmd) => cmd.to_command_bytes(),
+        Command::Reference(cmd) => cmd.to_command_bytes(),
+        Command::Configuration(cmd) => cmd.to_command_bytes(),
+        Command::ColorCorrection(cmd) => cmd.to_command_bytes(),
+        Command::Media(cmd) => cmd.to_command_bytes(),
+        Command::PtzControl(cmd) => cmd.to_command_bytes(),
+        Command::Metadata(cmd) => cmd.to_command_bytes(),
+        Command::Unknown {
+            category,
+            parameter,
+            data_type,
+            operation,
+            payload,
+        } => {
+            let mut cmd_buffer = vec![*category, *parameter, *data_type, *operation];
+            cmd_buffer.extend_from_slice(payload);
+            Ok(cmd_buffer)
+        }
+    }
+}
+
+/// Checks every `(packet, command)` pair of a command group, the packet has to parse into the
+/// command and the command has to encode back into the same packet.
+#[cfg(test)]
+pub(crate) fn assert_every_parameter<C: core::fmt::Debug + PartialEq>(
+    every_parameter: Vec<(Vec<u8>, C)>,
+    parse_command: fn(CommandData) -> Result<C, EldritchError>,
+    to_command_bytes: fn(&C) -> Result<Vec<u8>, EldritchError>,
+) {
+    for (bytes, command) in every_parameter {
+        let command_data = CommandData::new(&bytes).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data).as_ref(),
+            Ok(&command),
+            "{bytes:02x?}"
+        );
+        assert_eq!(to_command_bytes(&command), Ok(bytes), "{command:?}");
     }
 }
 
@@ -207,6 +440,395 @@ mod test {
         );
     }
 
+    #[test]
+    fn encode_video_mode_command() {
+        let cmd = Command::Video(video_commands::VideoCommand::VideoMode {
+            operation: Operation::Assign,
+            data: video_commands::VideoModeData {
+                frame_rate: 24,
+                m_rate: 1,
+                dimensions: 3,
+                interlaced: 0,
+                color_space: 0,
+            },
+        });
+        assert_eq!(
+            encode_command(&cmd).unwrap(),
+            vec![0x01, 0x00, 0x01, 0x00, 0x18, 0x01, 0x03, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn encode_subtract_gamma() {
+        let cmd_data = [
+            0x08, 0x01, 0x80, 0x01, 0x00, 0x00, 0x9a, 0xfd, 0x9a, 0xfd, 0x00, 0x00,
+        ];
+        let cmd = parse_command(&cmd_data).expect("Known good command data");
+        assert_eq!(encode_command(&cmd).unwrap(), cmd_data.to_vec());
+    }
+
+    #[test]
+    fn encode_lens_ois_command() {
+        let cmd = Command::Lens(lens_commands::LensCommand::OpticalImageStabalization {
+            operation: Operation::Assign,
+            data: true,
+        });
+        assert_eq!(
+            encode_command(&cmd).unwrap(),
+            vec![0x00, 0x06, 0x00, 0x00, 0x01]
+        );
+    }
+
+    #[test]
+    fn to_command_bytes_round_trip_every_group() {
+        let fpd = |raw_val: i16| FixedPointDecimal { raw_val };
+        let commands = vec![
+            Command::Lens(lens_commands::LensCommand::ApatureOrdinal {
+                operation: Operation::Increment,
+                data: 3,
+            }),
+            Command::Video(video_commands::VideoCommand::RecordingFormat {
+                operation: Operation::Assign,
+                data: video_commands::RecordingFormatData {
+                    file_frame_rate: 24,
+                    sensor_frame_rate: 48,
+                    frame_width: 1920,
+                    frame_height: 1080,
+                    flags: 0b0101,
+                },
+            }),
+            Command::Audio(audio_commands::AudioCommand::PhantomPower {
+                operation: Operation::Assign,
+                data: true,
+            }),
+            Command::Output(output_commands::OutputCommand::Overlays {
+                operation: Operation::Assign,
+                data: output_commands::OverlaysData {
+                    frame_guide_style: 2,
+                    frame_guide_opacity: 80,
+                    safe_area_percentage: 90,
+                    grid_style: 1,
+                },
+            }),
+            Command::Display(display_commands::DisplayCommand::OverlaysEnabled {
+                operation: Operation::Assign,
+                data: 0x0304,
+            }),
+            Command::Tally(tally_commands::TallyCommand::RearTallyBrightness {
+                operation: Operation::Assign,
+                data: fpd(0x0400),
+            }),
+            Command::Reference(reference_commands::ReferenceCommand::Offset {
+                operation: Operation::Increment,
+                data: -42,
+            }),
+            Command::Configuration(
+                configuration_commands::ConfigurationCommand::SystemLanguage {
+                    operation: Operation::Assign,
+                    data: String::from("en"),
+                },
+            ),
+            Command::Configuration(configuration_commands::ConfigurationCommand::Location {
+                operation: Operation::Assign,
+                data: configuration_commands::LocationData {
+                    laditude: 0x0102_0304_0506_0708,
+                    longitude: -1,
+                },
+            }),
+            Command::ColorCorrection(
+                color_correction_commands::ColorCorrectionCommand::LiftAdjust {
+                    operation: Operation::Assign,
+                    data: color_correction_commands::RedGreenBlueLuma {
+                        red: fpd(0x0001),
+                        green: fpd(0x0002),
+                        blue: fpd(0x0003),
+                        luma: fpd(0x0004),
+                    },
+                },
+            ),
+            Command::ColorCorrection(
+                color_correction_commands::ColorCorrectionCommand::CorrectionResetDefault,
+            ),
+            Command::Media(media_commands::MediaCommand::Codec {
+                operation: Operation::Assign,
+                data: media_commands::CodecData {
+                    basic_codec: 3,
+                    codec_varient: 1,
+                },
+            }),
+            Command::PtzControl(ptz_control_commands::PtzControlCommand::PanTiltVelocity {
+                operation: Operation::Assign,
+                data: ptz_control_commands::PanTiltVelocityData {
+                    pan_velocity: fpd(0x0400),
+                    tilt_velocity: fpd(-0x0400),
+                },
+            }),
+            Command::Metadata(metadata_commands::MetadataCommand::ProjectName {
+                operation: Operation::Assign,
+                data: String::from("Eldritch"),
+            }),
+        ];
+
+        for command in commands {
+            let cmd_data = encode_command(&command).unwrap();
+            assert_eq!(parse_command(&cmd_data), Ok(command));
+        }
+    }
+
+    #[test]
+    fn to_command_bytes_struct_declaration_order() {
+        let cmd = color_correction_commands::ColorCorrectionCommand::GainAdjust {
+            operation: Operation::Increment,
+            data: color_correction_commands::RedGreenBlueLuma {
+                red: FixedPointDecimal { raw_val: 0x0201 },
+                green: FixedPointDecimal { raw_val: 0x0403 },
+                blue: FixedPointDecimal { raw_val: 0x0605 },
+                luma: FixedPointDecimal { raw_val: 0x0807 },
+            },
+        };
+        assert_eq!(
+            cmd.to_command_bytes().unwrap(),
+            vec![0x08, 0x02, 0x80, 0x01, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]
+        );
+    }
+
+    #[test]
+    fn parse_unknown_category() {
+        let cmd_data = [0x7f, 0x00, 0x01, 0x00, 0x01];
+        assert_eq!(
+            parse_command(&cmd_data),
+            Err(EldritchError::UnknownCategory {
+                category: 0x7f,
+                raw: cmd_data.to_vec()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_unknown_parameter() {
+        let cmd_data = [0x00, 0x7f, 0x01, 0x00, 0x01];
+        assert_eq!(
+            parse_command(&cmd_data),
+            Err(EldritchError::UnknownParameter {
+                category: 0x00,
+                parameter: 0x7f,
+                raw: cmd_data.to_vec()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_unknown_data_type() {
+        let cmd_data = [0x00, 0x00, 0x42, 0x00, 0x33, 0x01];
+        assert_eq!(
+            parse_command(&cmd_data),
+            Err(EldritchError::UnknownDataType {
+                data_type: 0x42,
+                raw: cmd_data.to_vec()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_command_too_short() {
+        let cmd_data = [0x00, 0x06, 0x00];
+        assert_eq!(
+            parse_command(&cmd_data),
+            Err(EldritchError::InvalidCommandData {
+                message: "Command data shorter than 4 bytes",
+                data: cmd_data.to_vec()
+            })
+        );
+    }
+
+    #[test]
+    fn command_data_without_operation() {
+        let cmd_data = [0x00, 0x01, 0x00];
+        assert_eq!(
+            CommandData::new(&cmd_data),
+            Err(EldritchError::InvalidCommandData {
+                message: "Command data shorter than 4 bytes",
+                data: cmd_data.to_vec()
+            })
+        );
+        assert!(CommandData::new(&[0x00, 0x01, 0x00, 0x00]).is_ok());
+    }
+
+    #[test]
+    fn parse_bool_command_missing_data() {
+        let cmd_data = [0x00, 0x06, 0x00, 0x00];
+        assert_eq!(
+            parse_command(&cmd_data),
+            Err(EldritchError::InvalidCommandData {
+                message: "Missing data for bool",
+                data: cmd_data.to_vec()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_bool_command_bad_operation() {
+        let cmd_data = [0x02, 0x06, 0x00, 0x02, 0x01];
+        assert_eq!(
+            parse_command(&cmd_data),
+            Err(EldritchError::InvalidOperation {
+                operation: 0x02,
+                data_type: 0x00,
+                raw: cmd_data.to_vec()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_struct_command_short_data() {
+        let cmd_data = [0x08, 0x01, 0x80, 0x01, 0x00, 0x00, 0x9a, 0xfd];
+        assert!(matches!(
+            parse_command(&cmd_data),
+            Err(EldritchError::InvalidCommandData { .. })
+        ));
+    }
+
+    #[test]
+    fn protocol_enum_try_from() {
+        assert_eq!(
+            video_commands::AutoExposureMode::try_from(3),
+            Ok(video_commands::AutoExposureMode::IrisShutter)
+        );
+        assert_eq!(
+            metadata_commands::SceneTag::try_from(-1),
+            Ok(metadata_commands::SceneTag::None)
+        );
+        assert_eq!(
+            media_commands::Codec::try_from(4),
+            Err(EldritchError::DataOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn protocol_enum_into_i8() {
+        assert_eq!(i8::from(media_commands::Codec::BlackmagicRaw), 3);
+        assert_eq!(i8::from(metadata_commands::TakeTag::None), -1);
+    }
+
+    #[test]
+    fn protocol_enum_from_parsed_command() {
+        let cmd_data = [0x01, 0x0a, 0x01, 0x00, 0x04];
+        let Ok(Command::Video(video_commands::VideoCommand::SetAutoExposureMode { data, .. })) =
+            parse_command(&cmd_data)
+        else {
+            panic!("Known good auto exposure command");
+        };
+        assert_eq!(
+            video_commands::AutoExposureMode::try_from(data),
+            Ok(video_commands::AutoExposureMode::ShutterIris)
+        );
+    }
+
+    #[test]
+    fn wire_flags_insert_keeps_other_flags() {
+        let cmd_data = [0x04, 0x01, 0x02, 0x00, 0x02, 0x00];
+        let Ok(Command::Display(display_commands::DisplayCommand::OverlaysEnabled {
+            data, ..
+        })) = parse_command(&cmd_data)
+        else {
+            panic!("Known good overlays command");
+        };
+
+        let mut overlays = display_commands::DisplayOverlays::from(data);
+        overlays.insert(display_commands::DisplayOverlays::ZEBRA);
+        assert!(overlays.contains(display_commands::DisplayOverlays::FOCUS_ASSIST));
+        assert_eq!(i16::from(overlays), 0b011);
+
+        overlays.remove(display_commands::DisplayOverlays::FOCUS_ASSIST);
+        assert_eq!(i16::from(overlays), 0b001);
+    }
+
+    #[test]
+    fn wire_flags_keep_unknown_bits() {
+        let flags = media_commands::TransportFlags::from(0b1001_0011_u8 as i8);
+        assert!(flags.contains(
+            media_commands::TransportFlags::LOOP
+                | media_commands::TransportFlags::PLAY_ALL
+                | media_commands::TransportFlags::TIME_LAPSE_RECORDING
+        ));
+        assert_eq!(i8::from(flags), 0b1001_0011_u8 as i8);
+    }
+
+    #[test]
+    fn wire_flags_debug_names() {
+        let flags = video_commands::RecordingFormatFlags::SENSOR_M_RATE
+            | video_commands::RecordingFormatFlags::WINDOWED_MODE;
+        assert_eq!(
+            format!("{flags:?}"),
+            "RecordingFormatFlags(SENSOR_M_RATE | WINDOWED_MODE)"
+        );
+    }
+
+    #[test]
+    fn command_category_and_parameter() {
+        let cmd = Command::Video(video_commands::VideoCommand::ISO {
+            operation: Operation::Assign,
+            data: 800,
+        });
+        assert_eq!((cmd.category(), cmd.parameter()), (0x01, 0x0e));
+
+        let cmd = Command::ColorCorrection(
+            color_correction_commands::ColorCorrectionCommand::CorrectionResetDefault,
+        );
+        assert_eq!((cmd.category(), cmd.parameter()), (0x08, 0x07));
+    }
+
+    #[test]
+    fn groups_cover_every_command_group() {
+        for (name, category) in Command::GROUPS {
+            let text = format!("{name}.not_a_parameter = 1");
+            assert!(
+                matches!(
+                    text.parse::<Command>(),
+                    Err(EldritchError::InvalidText {
+                        message: "Unknown parameter",
+                        ..
+                    })
+                ),
+                "{name} is not a command group"
+            );
+            assert!(
+                matches!(
+                    parse_command(&[category, 0x7f, 0x00, 0x00]),
+                    Err(EldritchError::UnknownParameter { .. })
+                ),
+                "{category:#04x} is not a command group category"
+            );
+        }
+        assert!(Command::GROUPS.windows(2).all(|pair| pair[0].1 < pair[1].1));
+    }
+
+    #[test]
+    fn parse_unknown_command_round_trip() {
+        let cmd_data = [0x0d, 0x05, 0x05, 0x00, 0x41, 0x42];
+        let cmd = parse_command_or_unknown(&cmd_data);
+        assert_eq!(
+            cmd,
+            Ok(Command::Unknown {
+                category: 0x0d,
+                parameter: 0x05,
+                data_type: 0x05,
+                operation: 0x00,
+                payload: vec![0x41, 0x42],
+            })
+        );
+        assert_eq!(encode_command(&cmd.unwrap()).unwrap(), cmd_data.to_vec());
+    }
+
+    #[test]
+    fn parse_command_or_unknown_keeps_errors() {
+        let cmd_data = [0x00, 0x04, 0x01, 0x00, 0x10, 0x27];
+        assert!(matches!(
+            parse_command_or_unknown(&cmd_data),
+            Err(EldritchError::InvalidDataType { .. })
+        ));
+    }
+
     #[test]
     fn parse_command_data_success() {
         let cmd_data = [0x00, 0x06, 0x00, 0x00, 0x001];
@@ -269,6 +891,7 @@ mod test {
                     cmd,
                     Command::Video(super::video_commands::VideoCommand::NDFilterStop)
                 );
+                assert_eq!(super::encode_command(&cmd).unwrap(), cmd_data.to_vec());
             } else {
                 panic!();
             }
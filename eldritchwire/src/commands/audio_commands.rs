@@ -3,6 +3,8 @@ use crate::{EldritchError, FixedPointDecimal, Operation};
 use eldritchwire_macros::CommandGroup;
 
 #[derive(Clone, Debug, PartialEq, CommandGroup)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[command(category(0x02))]
 pub enum AudioCommand {
     #[command(parameter(0x00), data_type(0x80), bounds(lower(0.0), upper(1.0)))]
     MicLevel {
@@ -36,7 +38,132 @@ pub enum AudioCommand {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InputLevelsData {
     pub channel_one: FixedPointDecimal,
     pub channel_two: FixedPointDecimal,
 }
+
+protocol_enum! {
+    /// `AudioCommand::InputType`
+    pub enum AudioInputType {
+        InternalMic = 0,
+        LineLevel = 1,
+        LowMicLevel = 2,
+        HighMicLevel = 3,
+    }
+}
+
+#[cfg(test)]
+mod audio_commands_tests {
+    use super::*;
+    use crate::commands::assert_every_parameter;
+
+    /// One packet per parameter of the audio group, 2.0 to 2.6.
+    fn every_parameter() -> Vec<(Vec<u8>, AudioCommand)> {
+        let level = |raw_val| FixedPointDecimal { raw_val };
+        vec![
+            (
+                vec![0x02, 0x00, 0x80, 0x00, 0x00, 0x04],
+                AudioCommand::MicLevel {
+                    operation: Operation::Assign,
+                    data: level(0x0400),
+                },
+            ),
+            (
+                vec![0x02, 0x01, 0x80, 0x00, 0x00, 0x08],
+                AudioCommand::HeadphoneLevel {
+                    operation: Operation::Assign,
+                    data: level(0x0800),
+                },
+            ),
+            (
+                vec![0x02, 0x02, 0x80, 0x01, 0x9a, 0x01],
+                AudioCommand::HeadphoneProgramMix {
+                    operation: Operation::Increment,
+                    data: level(0x019a),
+                },
+            ),
+            (
+                vec![0x02, 0x03, 0x80, 0x00, 0x00, 0x02],
+                AudioCommand::SpeakerLevel {
+                    operation: Operation::Assign,
+                    data: level(0x0200),
+                },
+            ),
+            (
+                vec![0x02, 0x04, 0x01, 0x00, 0x02],
+                AudioCommand::InputType {
+                    operation: Operation::Assign,
+                    data: AudioInputType::LowMicLevel as i8,
+                },
+            ),
+            (
+                vec![0x02, 0x05, 0x80, 0x00, 0x00, 0x04, 0x00, 0x02],
+                AudioCommand::InputLevels {
+                    operation: Operation::Assign,
+                    data: InputLevelsData {
+                        channel_one: level(0x0400),
+                        channel_two: level(0x0200),
+                    },
+                },
+            ),
+            (
+                vec![0x02, 0x06, 0x00, 0x00, 0x01],
+                AudioCommand::PhantomPower {
+                    operation: Operation::Assign,
+                    data: true,
+                },
+            ),
+            (
+                vec![0x02, 0x06, 0x00, 0x01, 0x00],
+                AudioCommand::PhantomPower {
+                    operation: Operation::Toggle,
+                    data: false,
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn every_parameter_parses_and_round_trips() {
+        assert_every_parameter(
+            every_parameter(),
+            parse_command,
+            AudioCommand::to_command_bytes,
+        );
+    }
+
+    #[test]
+    fn parse_input_type_wrong_data_type() {
+        let command_data = [0x02, 0x04, 0x80, 0x00, 0x02, 0x00];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert!(matches!(
+            parse_command(command_data),
+            Err(EldritchError::InvalidDataType { .. })
+        ));
+    }
+
+    #[cfg(feature = "bounds-checked")]
+    #[test]
+    fn parse_input_type_out_of_bounds() {
+        let command_data = [0x02, 0x04, 0x01, 0x00, 0x04];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data),
+            Err(EldritchError::DataOutOfBounds)
+        );
+    }
+
+    #[cfg(feature = "bounds-checked")]
+    #[test]
+    fn parse_mic_level_out_of_bounds() {
+        // Value 1.1 (08cc) is above the bound of 1.0
+        let command_data = [0x02, 0x00, 0x80, 0x00, 0xcc, 0x08];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data),
+            Err(EldritchError::DataOutOfBounds)
+        );
+    }
+}
@@ -2,23 +2,45 @@ use crate::{commands::CommandData, EldritchError, FixedPointDecimal, Operation};
 use eldritchwire_macros::CommandGroup;
 
 #[derive(Clone, Debug, PartialEq, CommandGroup)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[command(category(0x08))]
 pub enum ColorCorrectionCommand {
-    #[command(parameter(0x00), data_type(0x80), data(red, green, blue, luma))]
+    #[command(
+        parameter(0x00),
+        data_type(0x80),
+        data(red, green, blue, luma),
+        bounds(lower(-2.0), upper(2.0))
+    )]
     LiftAdjust {
         operation: Operation,
         data: RedGreenBlueLuma,
     },
-    #[command(parameter(0x01), data_type(0x80), data(red, green, blue, luma))]
+    #[command(
+        parameter(0x01),
+        data_type(0x80),
+        data(red, green, blue, luma),
+        bounds(lower(-4.0), upper(4.0))
+    )]
     GammaAdjust {
         operation: Operation,
         data: RedGreenBlueLuma,
     },
-    #[command(parameter(0x02), data_type(0x80), data(red, green, blue, luma))]
+    #[command(
+        parameter(0x02),
+        data_type(0x80),
+        data(red, green, blue, luma),
+        bounds(lower(0.0), upper(16.0))
+    )]
     GainAdjust {
         operation: Operation,
         data: RedGreenBlueLuma,
     },
-    #[command(parameter(0x03), data_type(0x80), data(red, green, blue, luma))]
+    #[command(
+        parameter(0x03),
+        data_type(0x80),
+        data(red, green, blue, luma),
+        bounds(lower(-8.0), upper(8.0))
+    )]
     OffsetAdjust {
         operation: Operation,
         data: RedGreenBlueLuma,
@@ -43,6 +65,7 @@ pub enum ColorCorrectionCommand {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RedGreenBlueLuma {
     pub red: FixedPointDecimal,
     pub green: FixedPointDecimal,
@@ -51,18 +74,124 @@ pub struct RedGreenBlueLuma {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ContrastAdjustData {
     pub pivot: FixedPointDecimal,
     pub adj: FixedPointDecimal,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColorAdjustData {
     pub hue: FixedPointDecimal,
     pub sat: FixedPointDecimal,
 }
 
-pub type LiftAdjustData = RedGreenBlueLuma;
-pub type GammaAdjustData = RedGreenBlueLuma;
-pub type GainAdjustData = RedGreenBlueLuma;
-pub type OffsetAdjustData = RedGreenBlueLuma;
+#[cfg(test)]
+mod color_correction_commands_tests {
+    use super::*;
+    use crate::commands::assert_every_parameter;
+
+    /// One packet per parameter of the color correction group, 8.0 to 8.7.
+    fn every_parameter() -> Vec<(Vec<u8>, ColorCorrectionCommand)> {
+        let fixed = |raw_val| FixedPointDecimal { raw_val };
+        let rgbl = |red, green, blue, luma| RedGreenBlueLuma {
+            red: fixed(red),
+            green: fixed(green),
+            blue: fixed(blue),
+            luma: fixed(luma),
+        };
+        vec![
+            (
+                vec![
+                    0x08, 0x00, 0x80, 0x00, 0x00, 0xff, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+                ],
+                ColorCorrectionCommand::LiftAdjust {
+                    operation: Operation::Assign,
+                    data: rgbl(-0x0100, 0x0000, 0x0100, 0x0000),
+                },
+            ),
+            (
+                vec![
+                    0x08, 0x01, 0x80, 0x01, 0x66, 0x00, 0x66, 0x00, 0x66, 0x00, 0x00, 0x00,
+                ],
+                ColorCorrectionCommand::GammaAdjust {
+                    operation: Operation::Increment,
+                    data: rgbl(0x0066, 0x0066, 0x0066, 0x0000),
+                },
+            ),
+            (
+                vec![
+                    0x08, 0x02, 0x80, 0x00, 0x00, 0x08, 0x00, 0x08, 0x00, 0x10, 0x00, 0x08,
+                ],
+                ColorCorrectionCommand::GainAdjust {
+                    operation: Operation::Assign,
+                    data: rgbl(0x0800, 0x0800, 0x1000, 0x0800),
+                },
+            ),
+            (
+                vec![
+                    0x08, 0x03, 0x80, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0xfe, 0x00, 0x00,
+                ],
+                ColorCorrectionCommand::OffsetAdjust {
+                    operation: Operation::Assign,
+                    data: rgbl(0x0200, 0x0000, -0x0200, 0x0000),
+                },
+            ),
+            (
+                vec![0x08, 0x04, 0x80, 0x00, 0x00, 0x04, 0x00, 0x08],
+                ColorCorrectionCommand::ContrastAdjust {
+                    operation: Operation::Assign,
+                    data: ContrastAdjustData {
+                        pivot: fixed(0x0400),
+                        adj: fixed(0x0800),
+                    },
+                },
+            ),
+            (
+                vec![0x08, 0x05, 0x80, 0x00, 0x00, 0x08],
+                ColorCorrectionCommand::LumaMix {
+                    operation: Operation::Assign,
+                    data: fixed(0x0800),
+                },
+            ),
+            (
+                vec![0x08, 0x06, 0x80, 0x00, 0x00, 0x00, 0x00, 0x08],
+                ColorCorrectionCommand::ColorAdjust {
+                    operation: Operation::Assign,
+                    data: ColorAdjustData {
+                        hue: fixed(0x0000),
+                        sat: fixed(0x0800),
+                    },
+                },
+            ),
+            (
+                vec![0x08, 0x07, 0x00, 0x00],
+                ColorCorrectionCommand::CorrectionResetDefault,
+            ),
+        ]
+    }
+
+    #[test]
+    fn every_parameter_parses_and_round_trips() {
+        assert_every_parameter(
+            every_parameter(),
+            parse_command,
+            ColorCorrectionCommand::to_command_bytes,
+        );
+    }
+
+    #[cfg(feature = "bounds-checked")]
+    #[test]
+    fn parse_lift_field_out_of_bounds() {
+        // Blue at 2.5 (1400) is above the bound of 2.0, the other fields are 0.0
+        let command_data = [
+            0x08, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00,
+        ];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data),
+            Err(EldritchError::DataOutOfBounds)
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{commands::CommandData, EldritchError, Operation};
+use alloc::string::String;
 use eldritchwire_macros::CommandGroup;
 
 #[derive(Clone, Debug, PartialEq, CommandGroup)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[command(category(0x07))]
 pub enum ConfigurationCommand {
     #[command(parameter(0x00), data_type(3), data(time, date))]
     RealTimeClock {
@@ -10,9 +13,10 @@ pub enum ConfigurationCommand {
     },
     #[command(parameter(0x01), data_type(5))]
     SystemLanguage { operation: Operation, data: String },
-    #[command(parameter(0x03), data_type(3))]
+    /// Minutes offset from UTC.
+    #[command(parameter(0x02), data_type(3))]
     TimeZone { operation: Operation, data: i32 },
-    #[command(parameter(0x04), data_type(4), data(laditude, longitude))]
+    #[command(parameter(0x03), data_type(4), data(laditude, longitude))]
     Location {
         operation: Operation,
         data: LocationData,
@@ -20,13 +24,124 @@ pub enum ConfigurationCommand {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RealTimeClockData {
     pub time: i32,
     pub date: i32,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LocationData {
     pub laditude: i64,
     pub longitude: i64,
 }
+
+#[cfg(test)]
+mod configuration_commands_tests {
+    use super::*;
+    use crate::commands::assert_every_parameter;
+
+    /// One packet per parameter of the configuration group, 7.0 to 7.3.
+    fn every_parameter() -> Vec<(Vec<u8>, ConfigurationCommand)> {
+        vec![
+            (
+                vec![
+                    0x07, 0x00, 0x03, 0x00, // Command
+                    0x00, 0x00, 0x30, 0x10, // Time, 10:30:00:00 in BCD
+                    0x17, 0x10, 0x26, 0x20, // Date, 2026-10-17 in BCD
+                ],
+                ConfigurationCommand::RealTimeClock {
+                    operation: Operation::Assign,
+                    data: RealTimeClockData {
+                        time: 0x1030_0000,
+                        date: 0x2026_1017,
+                    },
+                },
+            ),
+            (
+                vec![0x07, 0x01, 0x05, 0x00, b'e', b'n'],
+                ConfigurationCommand::SystemLanguage {
+                    operation: Operation::Assign,
+                    data: String::from("en"),
+                },
+            ),
+            (
+                vec![0x07, 0x02, 0x03, 0x00, 0xc4, 0xff, 0xff, 0xff],
+                ConfigurationCommand::TimeZone {
+                    operation: Operation::Assign,
+                    data: -60,
+                },
+            ),
+            (
+                vec![
+                    0x07, 0x03, 0x04, 0x00, // Command
+                    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Latitude
+                    0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // Longitude
+                ],
+                ConfigurationCommand::Location {
+                    operation: Operation::Assign,
+                    data: LocationData {
+                        laditude: 1,
+                        longitude: -2,
+                    },
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn every_parameter_parses_and_round_trips() {
+        assert_every_parameter(
+            every_parameter(),
+            parse_command,
+            ConfigurationCommand::to_command_bytes,
+        );
+    }
+
+    #[test]
+    fn parse_time_zone_command() {
+        let command_data = [0x07, 0x02, 0x03, 0x00, 0xc4, 0xff, 0xff, 0xff];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data),
+            Ok(ConfigurationCommand::TimeZone {
+                operation: Operation::Assign,
+                data: -60
+            })
+        );
+    }
+
+    #[test]
+    fn parse_location_command() {
+        let command_data = [
+            0x07, 0x03, 0x04, 0x00, // Command
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Latitude
+            0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // Longitude
+        ];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data),
+            Ok(ConfigurationCommand::Location {
+                operation: Operation::Assign,
+                data: LocationData {
+                    laditude: 1,
+                    longitude: -2
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn time_zone_command_round_trip() {
+        let command = ConfigurationCommand::TimeZone {
+            operation: Operation::Assign,
+            data: 570,
+        };
+        let bytes = command.to_command_bytes().unwrap();
+        assert_eq!(bytes, [0x07, 0x02, 0x03, 0x00, 0x3a, 0x02, 0x00, 0x00]);
+
+        let command_data = CommandData::new(&bytes).expect("Known good packet data");
+        assert_eq!(parse_command(command_data), Ok(command));
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{commands::CommandData, EldritchError, FixedPointDecimal, Operation};
+use bitflags::bitflags;
 use eldritchwire_macros::CommandGroup;
 
 #[derive(Clone, Debug, PartialEq, CommandGroup)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[command(category(0x04))]
 pub enum DisplayCommand {
     #[command(parameter(0x00), data_type(0x80), bounds(lower(0.0), upper(1.0)))]
     Brightness {
@@ -31,10 +34,199 @@ pub enum DisplayCommand {
         operation: Operation,
         data: FocusAssistData,
     },
+    #[command(parameter(0x06), data_type(1), bounds(lower(0), upper(30)))]
+    ProgramReturnFeedDisplayTime { operation: Operation, data: i8 },
+    #[command(parameter(0x07), data_type(1), bounds(lower(0), upper(1)))]
+    TimecodeSource { operation: Operation, data: i8 },
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FocusAssistData {
     pub focus_assist_method: i8,
     pub focus_line_color: i8,
 }
+
+bitflags! {
+    /// `DisplayCommand::OverlaysEnabled`
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct DisplayOverlays: i16 {
+        const ZEBRA = 1 << 0;
+        const FOCUS_ASSIST = 1 << 1;
+        const FALSE_COLOR = 1 << 2;
+    }
+}
+
+wire_flags!(DisplayOverlays, i16);
+
+protocol_enum! {
+    /// `FocusAssistData::focus_assist_method`
+    pub enum FocusAssistMethod {
+        Peak = 0,
+        ColoredLines = 1,
+    }
+}
+
+protocol_enum! {
+    /// `FocusAssistData::focus_line_color`
+    pub enum FocusLineColor {
+        Red = 0,
+        Green = 1,
+        Blue = 2,
+        White = 3,
+        Black = 4,
+    }
+}
+
+protocol_enum! {
+    /// `DisplayCommand::TimecodeSource`
+    pub enum TimecodeSource {
+        Clip = 0,
+        Timecode = 1,
+    }
+}
+
+#[cfg(test)]
+mod display_commands_tests {
+    use super::*;
+    use crate::commands::assert_every_parameter;
+
+    /// One packet per parameter of the display group, 4.0 to 4.7.
+    fn every_parameter() -> Vec<(Vec<u8>, DisplayCommand)> {
+        let level = |raw_val| FixedPointDecimal { raw_val };
+        vec![
+            (
+                vec![0x04, 0x00, 0x80, 0x00, 0x00, 0x04],
+                DisplayCommand::Brightness {
+                    operation: Operation::Assign,
+                    data: level(0x0400),
+                },
+            ),
+            (
+                vec![0x04, 0x01, 0x02, 0x00, 0x05, 0x00],
+                DisplayCommand::OverlaysEnabled {
+                    operation: Operation::Assign,
+                    data: (DisplayOverlays::ZEBRA | DisplayOverlays::FALSE_COLOR).bits(),
+                },
+            ),
+            (
+                vec![0x04, 0x02, 0x80, 0x01, 0x33, 0x01],
+                DisplayCommand::ZebraLevel {
+                    operation: Operation::Increment,
+                    data: level(0x0133),
+                },
+            ),
+            (
+                vec![0x04, 0x03, 0x80, 0x00, 0x00, 0x08],
+                DisplayCommand::PeakingLevel {
+                    operation: Operation::Assign,
+                    data: level(0x0800),
+                },
+            ),
+            (
+                vec![0x04, 0x04, 0x01, 0x00, 0x0a],
+                DisplayCommand::ColorBarsDisplayTime {
+                    operation: Operation::Assign,
+                    data: 10,
+                },
+            ),
+            (
+                vec![0x04, 0x05, 0x01, 0x00, 0x01, 0x02],
+                DisplayCommand::FocusAssist {
+                    operation: Operation::Assign,
+                    data: FocusAssistData {
+                        focus_assist_method: FocusAssistMethod::ColoredLines.into(),
+                        focus_line_color: FocusLineColor::Blue.into(),
+                    },
+                },
+            ),
+            (
+                vec![0x04, 0x06, 0x01, 0x00, 0x05],
+                DisplayCommand::ProgramReturnFeedDisplayTime {
+                    operation: Operation::Assign,
+                    data: 5,
+                },
+            ),
+            (
+                vec![0x04, 0x07, 0x01, 0x00, 0x01],
+                DisplayCommand::TimecodeSource {
+                    operation: Operation::Assign,
+                    data: TimecodeSource::Timecode.into(),
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn every_parameter_parses_and_round_trips() {
+        assert_every_parameter(
+            every_parameter(),
+            parse_command,
+            DisplayCommand::to_command_bytes,
+        );
+    }
+
+    #[test]
+    fn parse_program_return_feed_command() {
+        let command_data = [0x04, 0x06, 0x01, 0x00, 0x0a];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data),
+            Ok(DisplayCommand::ProgramReturnFeedDisplayTime {
+                operation: Operation::Assign,
+                data: 10
+            })
+        );
+    }
+
+    #[test]
+    fn parse_timecode_source_command() {
+        let command_data = [0x04, 0x07, 0x01, 0x00, 0x01];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data),
+            Ok(DisplayCommand::TimecodeSource {
+                operation: Operation::Assign,
+                data: 1
+            })
+        );
+    }
+
+    #[cfg(feature = "bounds-checked")]
+    #[test]
+    fn parse_timecode_source_out_of_bounds() {
+        let command_data = [0x04, 0x07, 0x01, 0x00, 0x02];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data),
+            Err(EldritchError::DataOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn program_return_feed_command_round_trip() {
+        let command = DisplayCommand::ProgramReturnFeedDisplayTime {
+            operation: Operation::Increment,
+            data: 5,
+        };
+        let bytes = command.to_command_bytes().unwrap();
+        assert_eq!(bytes, [0x04, 0x06, 0x01, 0x01, 0x05]);
+
+        let command_data = CommandData::new(&bytes).expect("Known good packet data");
+        assert_eq!(parse_command(command_data), Ok(command));
+    }
+
+    #[test]
+    fn timecode_source_command_round_trip() {
+        let command = DisplayCommand::TimecodeSource {
+            operation: Operation::Assign,
+            data: 0,
+        };
+        let bytes = command.to_command_bytes().unwrap();
+        assert_eq!(bytes, [0x04, 0x07, 0x01, 0x00, 0x00]);
+
+        let command_data = CommandData::new(&bytes).expect("Known good packet data");
+        assert_eq!(parse_command(command_data), Ok(command));
+    }
+}
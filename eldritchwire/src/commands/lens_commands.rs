@@ -4,6 +4,8 @@ use eldritchwire_macros::CommandGroup;
 use super::CommandData;
 
 #[derive(Clone, Debug, PartialEq, CommandGroup)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[command(category(0x00))]
 pub enum LensCommand {
     #[command(parameter(0x00), data_type(128), bounds(lower(0.0), upper(1.0)))]
     Focus {
@@ -54,6 +56,7 @@ pub enum LensCommand {
 #[cfg(test)]
 mod lens_commands_tests {
     use super::*;
+    use crate::commands::assert_every_parameter;
 
     #[test]
     fn parse_command_data_assign() {
@@ -167,9 +170,10 @@ mod lens_commands_tests {
         let command = super::parse_command(command_data);
         assert_eq!(
             command,
-            Err(EldritchError::InvalidCommandData {
-                message: String::from("Invalid Data type for command"),
-                data: vec![0x00, 0x04, 0x01, 0x00, 0x10, 0x27],
+            Err(EldritchError::InvalidDataType {
+                data_type: 0x01,
+                expected: 0x02,
+                raw: vec![0x00, 0x04, 0x01, 0x00, 0x10, 0x27],
             })
         );
     }
@@ -245,9 +249,10 @@ mod lens_commands_tests {
         let command = super::parse_command(command_data);
         assert_eq!(
             command,
-            Err(EldritchError::InvalidCommandData {
-                message: String::from("Invalid Data type for command"),
-                data: vec![0x00, 0x07, 0x01, 0x00, 0x10, 0x00],
+            Err(EldritchError::InvalidDataType {
+                data_type: 0x01,
+                expected: 0x02,
+                raw: vec![0x00, 0x07, 0x01, 0x00, 0x10, 0x00],
             })
         );
     }
@@ -291,9 +296,10 @@ mod lens_commands_tests {
         let command = super::parse_command(command_data);
         assert_eq!(
             command,
-            Err(EldritchError::InvalidCommandData {
-                message: String::from("Invalid Data type for command"),
-                data: vec![0x00, 0x08, 0x01, 0x00, 0xcc, 0x08]
+            Err(EldritchError::InvalidDataType {
+                data_type: 0x01,
+                expected: 0x80,
+                raw: vec![0x00, 0x08, 0x01, 0x00, 0xcc, 0x08],
             })
         );
     }
@@ -321,13 +327,133 @@ mod lens_commands_tests {
         let command = super::parse_command(command_data);
         assert_eq!(
             command,
-            Err(EldritchError::InvalidCommandData {
-                message: String::from("Invalid Data type for command"),
-                data: vec![0x00, 0x09, 0x01, 0x00, 0xcc, 0x08]
+            Err(EldritchError::InvalidDataType {
+                data_type: 0x01,
+                expected: 0x80,
+                raw: vec![0x00, 0x09, 0x01, 0x00, 0xcc, 0x08],
             })
         );
     }
 
+    #[test]
+    fn focus_command_to_command_bytes() {
+        let command = LensCommand::Focus {
+            operation: Operation::Increment,
+            data: FixedPointDecimal {
+                raw_val: 0x0133u16 as i16,
+            },
+        };
+        assert_eq!(
+            command.to_command_bytes().unwrap(),
+            vec![0x00, 0x00, 0x80, 0x01, 0x33, 0x01]
+        );
+    }
+
+    #[test]
+    fn auto_focus_command_to_command_bytes() {
+        assert_eq!(
+            LensCommand::InstantaneousAutoFocus
+                .to_command_bytes()
+                .unwrap(),
+            vec![0x00, 0x01, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn absolute_zoom_mm_command_round_trip() {
+        let command_data = [0x00, 0x07, 0x02, 0x01, 0x10, 0x00];
+        let command = super::parse_command(CommandData::new(&command_data).expect("Should parse"))
+            .expect("Known good command data");
+        assert_eq!(command.to_command_bytes().unwrap(), command_data.to_vec());
+    }
+
+    /// One packet per parameter of the lens group, 0.0 to 0.9.
+    fn every_parameter() -> Vec<(Vec<u8>, LensCommand)> {
+        let fixed = |raw_val| FixedPointDecimal { raw_val };
+        vec![
+            (
+                vec![0x00, 0x00, 0x80, 0x00, 0x00, 0x04],
+                LensCommand::Focus {
+                    operation: Operation::Assign,
+                    data: fixed(0x0400),
+                },
+            ),
+            (
+                vec![0x00, 0x01, 0x00, 0x00],
+                LensCommand::InstantaneousAutoFocus,
+            ),
+            (
+                vec![0x00, 0x02, 0x80, 0x00, 0x00, 0x20],
+                LensCommand::ApatureFStop {
+                    operation: Operation::Assign,
+                    data: fixed(0x2000),
+                },
+            ),
+            (
+                vec![0x00, 0x03, 0x80, 0x01, 0x9a, 0x01],
+                LensCommand::ApatureNormalized {
+                    operation: Operation::Increment,
+                    data: fixed(0x019a),
+                },
+            ),
+            (
+                vec![0x00, 0x04, 0x02, 0x00, 0x05, 0x00],
+                LensCommand::ApatureOrdinal {
+                    operation: Operation::Assign,
+                    data: 5,
+                },
+            ),
+            (
+                vec![0x00, 0x05, 0x00, 0x00],
+                LensCommand::InstantaneousAutoApature,
+            ),
+            (
+                vec![0x00, 0x06, 0x00, 0x00, 0x01],
+                LensCommand::OpticalImageStabalization {
+                    operation: Operation::Assign,
+                    data: true,
+                },
+            ),
+            (
+                vec![0x00, 0x06, 0x00, 0x01, 0x00],
+                LensCommand::OpticalImageStabalization {
+                    operation: Operation::Toggle,
+                    data: false,
+                },
+            ),
+            (
+                vec![0x00, 0x07, 0x02, 0x00, 0x32, 0x00],
+                LensCommand::AbsoluteZoomMM {
+                    operation: Operation::Assign,
+                    data: 50,
+                },
+            ),
+            (
+                vec![0x00, 0x08, 0x80, 0x00, 0x00, 0x06],
+                LensCommand::AbsoluteZoomNormalized {
+                    operation: Operation::Assign,
+                    data: fixed(0x0600),
+                },
+            ),
+            (
+                vec![0x00, 0x09, 0x80, 0x00, 0x00, 0xfc],
+                LensCommand::AbsoluteZoomContinuous {
+                    operation: Operation::Assign,
+                    data: fixed(0xfc00u16 as i16),
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn every_parameter_parses_and_round_trips() {
+        assert_every_parameter(
+            every_parameter(),
+            parse_command,
+            LensCommand::to_command_bytes,
+        );
+    }
+
     #[allow(unexpected_cfgs)]
     #[cfg(feature = "bounds-checked")]
     mod check_bounds {
@@ -1,7 +1,10 @@
 use crate::{commands::CommandData, EldritchError, Operation};
+use bitflags::bitflags;
 use eldritchwire_macros::CommandGroup;
 
 #[derive(Clone, Debug, PartialEq, CommandGroup)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[command(category(0x0a))]
 pub enum MediaCommand {
     #[command(parameter(0x00), data_type(1), data(basic_codec, codec_varient))]
     Codec {
@@ -17,15 +20,21 @@ pub enum MediaCommand {
         operation: Operation,
         data: TransportModeData,
     },
+    #[command(parameter(0x02), data_type(1), bounds(lower(0), upper(1)))]
+    PlaybackControl { operation: Operation, data: i8 },
+    #[command(parameter(0x03))]
+    StillCapture,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CodecData {
     pub basic_codec: i8,
     pub codec_varient: i8,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransportModeData {
     pub mode: i8,
     pub speed: i8,
@@ -33,3 +42,107 @@ pub struct TransportModeData {
     pub slot_one_storage_medium: i8,
     pub slot_two_storage_medium: i8,
 }
+
+protocol_enum! {
+    /// `CodecData::basic_codec`
+    pub enum Codec {
+        Raw = 0,
+        DNxHD = 1,
+        ProRes = 2,
+        BlackmagicRaw = 3,
+    }
+}
+
+protocol_enum! {
+    /// `TransportModeData::mode`
+    pub enum TransportMode {
+        Preview = 0,
+        Play = 1,
+        Record = 2,
+    }
+}
+
+protocol_enum! {
+    /// `TransportModeData::slot_one_storage_medium` and `slot_two_storage_medium`
+    pub enum StorageMedium {
+        CFast = 0,
+        Sd = 1,
+        SsdRecorder = 2,
+        Usb = 3,
+    }
+}
+
+protocol_enum! {
+    /// `MediaCommand::PlaybackControl`
+    pub enum PlaybackControl {
+        PreviousClip = 0,
+        NextClip = 1,
+    }
+}
+
+bitflags! {
+    /// `TransportModeData::flags`
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct TransportFlags: i8 {
+        const LOOP = 1 << 0;
+        const PLAY_ALL = 1 << 1;
+        const DISK_ONE_ACTIVE = 1 << 5;
+        const DISK_TWO_ACTIVE = 1 << 6;
+        const TIME_LAPSE_RECORDING = i8::MIN;
+    }
+}
+
+wire_flags!(TransportFlags, i8);
+
+#[cfg(test)]
+mod media_commands_tests {
+    use super::*;
+    use crate::commands::assert_every_parameter;
+
+    /// One packet per parameter of the media group, 10.0 to 10.3.
+    fn every_parameter() -> Vec<(Vec<u8>, MediaCommand)> {
+        vec![
+            (
+                vec![0x0a, 0x00, 0x01, 0x00, 0x03, 0x01],
+                MediaCommand::Codec {
+                    operation: Operation::Assign,
+                    data: CodecData {
+                        basic_codec: Codec::BlackmagicRaw.into(),
+                        codec_varient: 1,
+                    },
+                },
+            ),
+            (
+                vec![0x0a, 0x01, 0x01, 0x00, 0x01, 0x01, 0x21, 0x01, 0x00],
+                MediaCommand::TransportMode {
+                    operation: Operation::Assign,
+                    data: TransportModeData {
+                        mode: TransportMode::Play.into(),
+                        speed: 1,
+                        flags: (TransportFlags::LOOP | TransportFlags::DISK_ONE_ACTIVE).into(),
+                        slot_one_storage_medium: StorageMedium::Sd.into(),
+                        slot_two_storage_medium: StorageMedium::CFast.into(),
+                    },
+                },
+            ),
+            (
+                vec![0x0a, 0x02, 0x01, 0x00, 0x01],
+                MediaCommand::PlaybackControl {
+                    operation: Operation::Assign,
+                    data: PlaybackControl::NextClip.into(),
+                },
+            ),
+            (vec![0x0a, 0x03, 0x00, 0x00], MediaCommand::StillCapture),
+        ]
+    }
+
+    #[test]
+    fn every_parameter_parses_and_round_trips() {
+        assert_every_parameter(
+            every_parameter(),
+            parse_command,
+            MediaCommand::to_command_bytes,
+        );
+    }
+}
@@ -0,0 +1,386 @@
+use crate::{commands::CommandData, EldritchError, Operation};
+use alloc::string::String;
+use eldritchwire_macros::CommandGroup;
+
+/// Slate metadata. String fields are fixed length on the camera, longer values are rejected when
+/// parsing and trailing NUL padding is dropped.
+#[derive(Clone, Debug, PartialEq, CommandGroup)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[command(category(0x0c))]
+pub enum MetadataCommand {
+    #[command(parameter(0x00), data_type(2), bounds(lower(0), upper(999)))]
+    Reel { operation: Operation, data: i16 },
+    #[command(parameter(0x01), data_type(1), data(scene_tag, location, time_of_day))]
+    SceneTags {
+        operation: Operation,
+        data: SceneTagsData,
+    },
+    #[command(parameter(0x02), data_type(5), max_length(5))]
+    Scene { operation: Operation, data: String },
+    #[command(parameter(0x03), data_type(1), data(number, tag))]
+    Take {
+        operation: Operation,
+        data: TakeData,
+    },
+    #[command(parameter(0x04), data_type(0))]
+    GoodTake { operation: Operation, data: bool },
+    #[command(parameter(0x05), data_type(5), max_length(1))]
+    CameraId { operation: Operation, data: String },
+    #[command(parameter(0x06), data_type(5), max_length(32))]
+    CameraOperator { operation: Operation, data: String },
+    #[command(parameter(0x07), data_type(5), max_length(32))]
+    Director { operation: Operation, data: String },
+    #[command(parameter(0x08), data_type(5), max_length(32))]
+    ProjectName { operation: Operation, data: String },
+    #[command(parameter(0x09), data_type(5), max_length(56))]
+    LensType { operation: Operation, data: String },
+    #[command(parameter(0x0a), data_type(5), max_length(20))]
+    Iris { operation: Operation, data: String },
+    #[command(parameter(0x0b), data_type(5), max_length(29))]
+    FocalLength { operation: Operation, data: String },
+    #[command(parameter(0x0c), data_type(5), max_length(49))]
+    Distance { operation: Operation, data: String },
+    #[command(parameter(0x0d), data_type(5), max_length(20))]
+    Filter { operation: Operation, data: String },
+    #[command(parameter(0x0e), data_type(1), bounds(lower(0), upper(1)))]
+    SlateMode { operation: Operation, data: i8 },
+    #[command(parameter(0x0f), data_type(5), max_length(32))]
+    SlateTarget { operation: Operation, data: String },
+}
+
+/// Raw codes for [`SceneTag`], [`SceneLocation`] and [`TimeOfDay`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SceneTagsData {
+    pub scene_tag: i8,
+    pub location: i8,
+    pub time_of_day: i8,
+}
+
+/// `number` is 1 - 99, `tag` is the raw code for a [`TakeTag`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TakeData {
+    pub number: i8,
+    pub tag: i8,
+}
+
+protocol_enum! {
+    /// `SceneTagsData::scene_tag`
+    pub enum SceneTag {
+        None = -1,
+        WideShot = 0,
+        CloseUp = 1,
+        MediumShot = 2,
+        LongShot = 3,
+        MediumCloseUp = 4,
+        ExtremeCloseUp = 5,
+    }
+}
+
+protocol_enum! {
+    /// `SceneTagsData::location`
+    pub enum SceneLocation {
+        Exterior = 0,
+        Interior = 1,
+    }
+}
+
+protocol_enum! {
+    /// `SceneTagsData::time_of_day`
+    pub enum TimeOfDay {
+        Night = 0,
+        Day = 1,
+    }
+}
+
+protocol_enum! {
+    /// `TakeData::tag`
+    pub enum TakeTag {
+        None = -1,
+        PickUp = 0,
+        Vfx = 1,
+        Series = 2,
+    }
+}
+
+protocol_enum! {
+    /// `MetadataCommand::SlateMode`
+    pub enum SlateMode {
+        Recording = 0,
+        Playback = 1,
+    }
+}
+
+#[cfg(test)]
+mod metadata_commands_tests {
+    use super::*;
+    use crate::commands::assert_every_parameter;
+
+    /// One packet per parameter of the metadata group, 12.0 to 12.15.
+    fn every_parameter() -> Vec<(Vec<u8>, MetadataCommand)> {
+        let string =
+            |parameter, text: &str| [&[0x0c, parameter, 0x05, 0x00][..], text.as_bytes()].concat();
+        vec![
+            (
+                vec![0x0c, 0x00, 0x02, 0x00, 0x2a, 0x00],
+                MetadataCommand::Reel {
+                    operation: Operation::Assign,
+                    data: 42,
+                },
+            ),
+            (
+                vec![0x0c, 0x01, 0x01, 0x00, 0xff, 0x01, 0x00],
+                MetadataCommand::SceneTags {
+                    operation: Operation::Assign,
+                    data: SceneTagsData {
+                        scene_tag: SceneTag::None.into(),
+                        location: SceneLocation::Interior.into(),
+                        time_of_day: TimeOfDay::Night.into(),
+                    },
+                },
+            ),
+            (
+                string(0x02, "12A"),
+                MetadataCommand::Scene {
+                    operation: Operation::Assign,
+                    data: String::from("12A"),
+                },
+            ),
+            (
+                vec![0x0c, 0x03, 0x01, 0x00, 0x07, 0x01],
+                MetadataCommand::Take {
+                    operation: Operation::Assign,
+                    data: TakeData {
+                        number: 7,
+                        tag: TakeTag::Vfx.into(),
+                    },
+                },
+            ),
+            (
+                vec![0x0c, 0x04, 0x00, 0x00, 0x01],
+                MetadataCommand::GoodTake {
+                    operation: Operation::Assign,
+                    data: true,
+                },
+            ),
+            (
+                vec![0x0c, 0x04, 0x00, 0x01, 0x00],
+                MetadataCommand::GoodTake {
+                    operation: Operation::Toggle,
+                    data: false,
+                },
+            ),
+            (
+                string(0x05, "A"),
+                MetadataCommand::CameraId {
+                    operation: Operation::Assign,
+                    data: String::from("A"),
+                },
+            ),
+            (
+                string(0x06, "Ada"),
+                MetadataCommand::CameraOperator {
+                    operation: Operation::Assign,
+                    data: String::from("Ada"),
+                },
+            ),
+            (
+                string(0x07, "Kubrick"),
+                MetadataCommand::Director {
+                    operation: Operation::Assign,
+                    data: String::from("Kubrick"),
+                },
+            ),
+            (
+                string(0x08, "Eldritch"),
+                MetadataCommand::ProjectName {
+                    operation: Operation::Assign,
+                    data: String::from("Eldritch"),
+                },
+            ),
+            (
+                string(0x09, "35mm Prime"),
+                MetadataCommand::LensType {
+                    operation: Operation::Assign,
+                    data: String::from("35mm Prime"),
+                },
+            ),
+            (
+                string(0x0a, "f2.8"),
+                MetadataCommand::Iris {
+                    operation: Operation::Assign,
+                    data: String::from("f2.8"),
+                },
+            ),
+            (
+                string(0x0b, "35mm"),
+                MetadataCommand::FocalLength {
+                    operation: Operation::Assign,
+                    data: String::from("35mm"),
+                },
+            ),
+            (
+                string(0x0c, "3m"),
+                MetadataCommand::Distance {
+                    operation: Operation::Assign,
+                    data: String::from("3m"),
+                },
+            ),
+            (
+                string(0x0d, "ND4"),
+                MetadataCommand::Filter {
+                    operation: Operation::Assign,
+                    data: String::from("ND4"),
+                },
+            ),
+            (
+                vec![0x0c, 0x0e, 0x01, 0x00, 0x01],
+                MetadataCommand::SlateMode {
+                    operation: Operation::Assign,
+                    data: SlateMode::Playback.into(),
+                },
+            ),
+            (
+                string(0x0f, "cam1"),
+                MetadataCommand::SlateTarget {
+                    operation: Operation::Assign,
+                    data: String::from("cam1"),
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn every_parameter_parses_and_round_trips() {
+        assert_every_parameter(
+            every_parameter(),
+            parse_command,
+            MetadataCommand::to_command_bytes,
+        );
+    }
+
+    #[test]
+    fn parse_reel_command() {
+        let command_data = [0x0c, 0x00, 0x02, 0x00, 0x2a, 0x00];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data),
+            Ok(MetadataCommand::Reel {
+                operation: Operation::Assign,
+                data: 42
+            })
+        );
+    }
+
+    #[test]
+    fn parse_take_command() {
+        let command_data = [0x0c, 0x03, 0x01, 0x00, 0x07, 0x01];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data),
+            Ok(MetadataCommand::Take {
+                operation: Operation::Assign,
+                data: TakeData { number: 7, tag: 1 }
+            })
+        );
+    }
+
+    #[test]
+    fn parse_string_drops_nul_padding() {
+        let command_data = [0x0c, 0x02, 0x05, 0x00, b'1', b'2', b'A', 0x00, 0x00];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data),
+            Ok(MetadataCommand::Scene {
+                operation: Operation::Assign,
+                data: String::from("12A")
+            })
+        );
+    }
+
+    #[test]
+    fn parse_empty_string() {
+        let command_data = [0x0c, 0x05, 0x05, 0x00];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data),
+            Ok(MetadataCommand::CameraId {
+                operation: Operation::Assign,
+                data: String::new()
+            })
+        );
+    }
+
+    #[cfg(feature = "bounds-checked")]
+    #[test]
+    fn parse_string_too_long() {
+        let command_data = [0x0c, 0x02, 0x05, 0x00, b'1', b'2', b'3', b'4', b'5', b'6'];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data),
+            Err(EldritchError::DataOutOfBounds)
+        );
+    }
+
+    #[cfg(feature = "bounds-checked")]
+    #[test]
+    fn encode_string_too_long() {
+        let command = MetadataCommand::ProjectName {
+            operation: Operation::Assign,
+            data: "A".repeat(33),
+        };
+        assert_eq!(
+            command.to_command_bytes(),
+            Err(EldritchError::DataOutOfBounds)
+        );
+
+        let command = MetadataCommand::CameraId {
+            operation: Operation::Assign,
+            data: String::from("AB"),
+        };
+        assert_eq!(
+            command.to_command_bytes(),
+            Err(EldritchError::DataOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn parse_string_increment() {
+        let command_data = [0x0c, 0x07, 0x05, 0x01, b'A'];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert!(matches!(
+            parse_command(command_data),
+            Err(EldritchError::InvalidOperation { .. })
+        ));
+    }
+
+    #[test]
+    fn director_command_to_command_bytes() {
+        let command = MetadataCommand::Director {
+            operation: Operation::Assign,
+            data: String::from("Kubrick"),
+        };
+        assert_eq!(
+            command.to_command_bytes().unwrap(),
+            [&[0x0c, 0x07, 0x05, 0x00][..], b"Kubrick"].concat()
+        );
+    }
+
+    #[test]
+    fn scene_tags_command_round_trip() {
+        let command = MetadataCommand::SceneTags {
+            operation: Operation::Assign,
+            data: SceneTagsData {
+                scene_tag: -1,
+                location: 1,
+                time_of_day: 0,
+            },
+        };
+        let bytes = command.to_command_bytes().unwrap();
+        assert_eq!(bytes, [0x0c, 0x01, 0x01, 0x00, 0xff, 0x01, 0x00]);
+
+        let command_data = CommandData::new(&bytes).expect("Known good packet data");
+        assert_eq!(parse_command(command_data), Ok(command));
+    }
+}
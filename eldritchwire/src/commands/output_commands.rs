@@ -1,7 +1,10 @@
 use crate::{commands::CommandData, EldritchError, FixedPointDecimal, Operation};
+use bitflags::bitflags;
 use eldritchwire_macros::CommandGroup;
 
 #[derive(Clone, Debug, PartialEq, CommandGroup)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[command(category(0x03))]
 pub enum OutputCommand {
     #[command(parameter(0x00), data_type(2))]
     OverlayEnabled { operation: Operation, data: i16 },
@@ -29,9 +32,112 @@ pub enum OutputCommand {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OverlaysData {
     pub frame_guide_style: i8,
     pub frame_guide_opacity: i8,
     pub safe_area_percentage: i8,
     pub grid_style: i8,
 }
+
+bitflags! {
+    /// `OutputCommand::OverlayEnabled`
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct OutputOverlays: i16 {
+        const DISPLAY_STATUS = 1 << 2;
+        const FRAME_GUIDES = 1 << 3;
+    }
+}
+
+wire_flags!(OutputOverlays, i16);
+
+bitflags! {
+    /// `OverlaysData::grid_style`
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct GridStyle: i8 {
+        const THIRDS = 1 << 0;
+        const CROSS_HAIRS = 1 << 1;
+        const CENTER_DOT = 1 << 2;
+        const HORIZON = 1 << 3;
+    }
+}
+
+wire_flags!(GridStyle, i8);
+
+#[cfg(test)]
+mod output_commands_tests {
+    use super::*;
+    use crate::commands::assert_every_parameter;
+
+    /// One packet per parameter of the output group, 3.0 to 3.3.
+    fn every_parameter() -> Vec<(Vec<u8>, OutputCommand)> {
+        vec![
+            (
+                vec![0x03, 0x00, 0x02, 0x00, 0x0c, 0x00],
+                OutputCommand::OverlayEnabled {
+                    operation: Operation::Assign,
+                    data: (OutputOverlays::DISPLAY_STATUS | OutputOverlays::FRAME_GUIDES).bits(),
+                },
+            ),
+            (
+                vec![0x03, 0x01, 0x01, 0x00, 0x03],
+                OutputCommand::FrameGuideStyles {
+                    operation: Operation::Assign,
+                    data: 3,
+                },
+            ),
+            (
+                vec![0x03, 0x02, 0x80, 0x00, 0x00, 0x06],
+                OutputCommand::FrameGuidesOpacity {
+                    operation: Operation::Assign,
+                    data: FixedPointDecimal { raw_val: 0x0600 },
+                },
+            ),
+            (
+                vec![0x03, 0x03, 0x01, 0x00, 0x01, 0x64, 0x5a, 0x03],
+                OutputCommand::Overlays {
+                    operation: Operation::Assign,
+                    data: OverlaysData {
+                        frame_guide_style: 1,
+                        frame_guide_opacity: 100,
+                        safe_area_percentage: 90,
+                        grid_style: (GridStyle::THIRDS | GridStyle::CROSS_HAIRS).bits(),
+                    },
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn every_parameter_parses_and_round_trips() {
+        assert_every_parameter(
+            every_parameter(),
+            parse_command,
+            OutputCommand::to_command_bytes,
+        );
+    }
+
+    #[test]
+    fn parse_overlays_wrong_data_type() {
+        let command_data = [0x03, 0x03, 0x02, 0x00, 0x01, 0x00, 0x64, 0x00];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert!(matches!(
+            parse_command(command_data),
+            Err(EldritchError::InvalidDataType { .. })
+        ));
+    }
+
+    #[cfg(feature = "bounds-checked")]
+    #[test]
+    fn parse_frame_guides_opacity_below_bounds() {
+        // Value 0.05 (0066) is below the bound of 0.1
+        let command_data = [0x03, 0x02, 0x80, 0x00, 0x66, 0x00];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data),
+            Err(EldritchError::DataOutOfBounds)
+        );
+    }
+}
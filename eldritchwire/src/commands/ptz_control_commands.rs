@@ -2,6 +2,8 @@ use crate::{commands::CommandData, EldritchError, FixedPointDecimal, Operation};
 use eldritchwire_macros::CommandGroup;
 
 #[derive(Clone, Debug, PartialEq, CommandGroup)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[command(category(0x0b))]
 pub enum PtzControlCommand {
     #[command(parameter(0x00), data_type(0x80), data(pan_velocity, tilt_velocity))]
     PanTiltVelocity {
@@ -16,13 +18,65 @@ pub enum PtzControlCommand {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PanTiltVelocityData {
     pub pan_velocity: FixedPointDecimal,
     pub tilt_velocity: FixedPointDecimal,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MemoryPresetData {
     pub preset_command: i8,
     pub preset_slot: i8,
 }
+
+protocol_enum! {
+    /// `MemoryPresetData::preset_command`
+    pub enum PresetCommand {
+        Reset = 0,
+        Store = 1,
+        Recall = 2,
+    }
+}
+
+#[cfg(test)]
+mod ptz_control_commands_tests {
+    use super::*;
+    use crate::commands::assert_every_parameter;
+
+    /// One packet per parameter of the PTZ control group, 11.0 and 11.1.
+    fn every_parameter() -> Vec<(Vec<u8>, PtzControlCommand)> {
+        vec![
+            (
+                vec![0x0b, 0x00, 0x80, 0x00, 0x00, 0x04, 0x00, 0xfc],
+                PtzControlCommand::PanTiltVelocity {
+                    operation: Operation::Assign,
+                    data: PanTiltVelocityData {
+                        pan_velocity: FixedPointDecimal { raw_val: 0x0400 },
+                        tilt_velocity: FixedPointDecimal { raw_val: -0x0400 },
+                    },
+                },
+            ),
+            (
+                vec![0x0b, 0x01, 0x01, 0x00, 0x02, 0x03],
+                PtzControlCommand::MemoryPreset {
+                    operation: Operation::Assign,
+                    data: MemoryPresetData {
+                        preset_command: PresetCommand::Recall.into(),
+                        preset_slot: 3,
+                    },
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn every_parameter_parses_and_round_trips() {
+        assert_every_parameter(
+            every_parameter(),
+            parse_command,
+            PtzControlCommand::to_command_bytes,
+        );
+    }
+}
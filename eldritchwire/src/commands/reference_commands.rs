@@ -2,9 +2,82 @@ use crate::{commands::CommandData, EldritchError, Operation};
 use eldritchwire_macros::CommandGroup;
 
 #[derive(Clone, Debug, PartialEq, CommandGroup)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[command(category(0x06))]
 pub enum ReferenceCommand {
-    #[command(parameter(0x00), data_type(1), bounds(lower(0), upper(1)))]
+    /// 0 = Internal, 1 = Program, 2 = External
+    #[command(parameter(0x00), data_type(1), bounds(lower(0), upper(2)))]
     Source { operation: Operation, data: i8 },
     #[command(parameter(0x01), data_type(3))]
     Offset { operation: Operation, data: i32 },
 }
+
+protocol_enum! {
+    /// `ReferenceCommand::Source`
+    pub enum ReferenceSource {
+        Internal = 0,
+        Program = 1,
+        External = 2,
+    }
+}
+
+#[cfg(test)]
+mod reference_commands_tests {
+    use super::*;
+    use crate::commands::assert_every_parameter;
+
+    /// One packet per parameter of the reference group, 6.0 and 6.1.
+    fn every_parameter() -> Vec<(Vec<u8>, ReferenceCommand)> {
+        vec![
+            (
+                vec![0x06, 0x00, 0x01, 0x00, 0x02],
+                ReferenceCommand::Source {
+                    operation: Operation::Assign,
+                    data: ReferenceSource::External.into(),
+                },
+            ),
+            (
+                vec![0x06, 0x01, 0x03, 0x01, 0xd6, 0xff, 0xff, 0xff],
+                ReferenceCommand::Offset {
+                    operation: Operation::Increment,
+                    data: -42,
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn every_parameter_parses_and_round_trips() {
+        assert_every_parameter(
+            every_parameter(),
+            parse_command,
+            ReferenceCommand::to_command_bytes,
+        );
+    }
+
+    #[test]
+    fn parse_external_source_command() {
+        let command_data = [0x06, 0x00, 0x01, 0x00, 0x02];
+        let command_data = CommandData::new(&command_data).expect("Known good packet data");
+        assert_eq!(
+            parse_command(command_data),
+            Ok(ReferenceCommand::Source {
+                operation: Operation::Assign,
+                data: 2
+            })
+        );
+    }
+
+    #[test]
+    fn source_command_round_trip() {
+        let command = ReferenceCommand::Source {
+            operation: Operation::Assign,
+            data: 2,
+        };
+        let bytes = command.to_command_bytes().unwrap();
+        assert_eq!(bytes, [0x06, 0x00, 0x01, 0x00, 0x02]);
+
+        let command_data = CommandData::new(&bytes).expect("Known good packet data");
+        assert_eq!(parse_command(command_data), Ok(command));
+    }
+}
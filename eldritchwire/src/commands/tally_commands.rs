@@ -3,6 +3,8 @@ use eldritchwire_macros::CommandGroup;
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Clone, Debug, PartialEq, CommandGroup)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[command(category(0x05))]
 pub enum TallyCommand {
     #[command(parameter(0x00), data_type(128), bounds(lower(0.0), upper(1.0)))]
     TallyBrightness {
@@ -20,3 +22,46 @@ pub enum TallyCommand {
         data: FixedPointDecimal,
     },
 }
+
+#[cfg(test)]
+mod tally_commands_tests {
+    use super::*;
+    use crate::commands::assert_every_parameter;
+
+    /// One packet per parameter of the tally group, 5.0 to 5.2.
+    fn every_parameter() -> Vec<(Vec<u8>, TallyCommand)> {
+        let brightness = |raw_val| FixedPointDecimal { raw_val };
+        vec![
+            (
+                vec![0x05, 0x00, 0x80, 0x00, 0x00, 0x04],
+                TallyCommand::TallyBrightness {
+                    operation: Operation::Assign,
+                    data: brightness(0x0400),
+                },
+            ),
+            (
+                vec![0x05, 0x01, 0x80, 0x00, 0x00, 0x08],
+                TallyCommand::FrontTallyBrightness {
+                    operation: Operation::Assign,
+                    data: brightness(0x0800),
+                },
+            ),
+            (
+                vec![0x05, 0x02, 0x80, 0x01, 0x9a, 0x01],
+                TallyCommand::RearTallyBrightness {
+                    operation: Operation::Increment,
+                    data: brightness(0x019a),
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn every_parameter_parses_and_round_trips() {
+        assert_every_parameter(
+            every_parameter(),
+            parse_command,
+            TallyCommand::to_command_bytes,
+        );
+    }
+}
@@ -1,11 +1,14 @@
 #[cfg(not(feature = "ignore-nd-filter"))]
 use crate::FixedPointDecimal;
 use crate::{error::EldritchError, Operation};
+use bitflags::bitflags;
 use eldritchwire_macros::CommandGroup;
 
 use super::CommandData;
 
 #[derive(Clone, Debug, PartialEq, CommandGroup)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[command(category(0x01))]
 pub enum VideoCommand {
     #[command(
         parameter(0x00),
@@ -31,7 +34,7 @@ pub enum VideoCommand {
     ExposureUS { operation: Operation, data: i32 },
     #[command(parameter(0x06), data_type(2), bounds(lower(0)))]
     ExposureOrdinal { operation: Operation, data: i16 },
-    #[command(parameter(0x07), data_type(1), bounds(lower(0), upper(1)))]
+    #[command(parameter(0x07), data_type(1), bounds(lower(0), upper(2)))]
     DynamicRageMode { operation: Operation, data: i8 },
     #[command(parameter(0x08), data_type(1), bounds(lower(0), upper(3)))]
     VideoSharpeningLevel { operation: Operation, data: i8 },
@@ -66,12 +69,15 @@ pub enum VideoCommand {
         operation: Operation,
         data: NDFilterStopData,
     },
+    // The payload is dropped when parsing. The data type is still declared so the encoder writes
+    // the command back as the fixed point value cameras send rather than as a void command.
     #[cfg(feature = "ignore-nd-filter")]
-    #[command(parameter(0x10))]
+    #[command(parameter(0x10), data_type(128), discard_data)]
     NDFilterStop,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VideoModeData {
     pub frame_rate: i8,
     pub m_rate: i8,
@@ -81,12 +87,14 @@ pub struct VideoModeData {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ManualWhiteBalanceData {
     pub color_temp: i16,
     pub tint: i16,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RecordingFormatData {
     pub file_frame_rate: i16,
     pub sensor_frame_rate: i16,
@@ -96,14 +104,254 @@ pub struct RecordingFormatData {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DisplayLUTData {
-    selected: i8,
-    enabled: i8,
+    pub selected: i8,
+    pub enabled: i8,
 }
 
 #[cfg(not(feature = "ignore-nd-filter"))]
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NDFilterStopData {
-    stop: FixedPointDecimal,
-    display_mode: FixedPointDecimal,
+    pub stop: FixedPointDecimal,
+    pub display_mode: FixedPointDecimal,
+}
+
+protocol_enum! {
+    /// `VideoModeData::dimensions`
+    pub enum VideoDimensions {
+        Ntsc = 0,
+        Pal = 1,
+        Hd720 = 2,
+        Hd1080 = 3,
+        TwoKDci = 4,
+        TwoK16By9 = 5,
+        Uhd = 6,
+        ThreeKAnamorphic = 7,
+        FourKDci = 8,
+        FourK16By9 = 9,
+        FourPointSixK2_4By1 = 10,
+        FourPointSixK = 11,
+    }
+}
+
+protocol_enum! {
+    /// `VideoModeData::color_space`
+    pub enum ColorSpace {
+        Yuv = 0,
+    }
+}
+
+protocol_enum! {
+    /// `VideoCommand::DynamicRageMode`
+    pub enum DynamicRangeMode {
+        Film = 0,
+        Video = 1,
+        ExtendedVideo = 2,
+    }
+}
+
+protocol_enum! {
+    /// `VideoCommand::VideoSharpeningLevel`
+    pub enum SharpeningLevel {
+        Off = 0,
+        Low = 1,
+        Medium = 2,
+        High = 3,
+    }
+}
+
+protocol_enum! {
+    /// `VideoCommand::SetAutoExposureMode`
+    pub enum AutoExposureMode {
+        Manual = 0,
+        Iris = 1,
+        Shutter = 2,
+        IrisShutter = 3,
+        ShutterIris = 4,
+    }
+}
+
+protocol_enum! {
+    /// `DisplayLUTData::selected`
+    pub enum DisplayLut {
+        None = 0,
+        Custom = 1,
+        FilmToVideo = 2,
+        FilmToExtendedVideo = 3,
+    }
+}
+
+bitflags! {
+    /// `RecordingFormatData::flags`
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct RecordingFormatFlags: i16 {
+        const FILE_M_RATE = 1 << 0;
+        const SENSOR_M_RATE = 1 << 1;
+        const SENSOR_OFF_SPEED = 1 << 2;
+        const INTERLACED = 1 << 3;
+        const WINDOWED_MODE = 1 << 4;
+    }
+}
+
+wire_flags!(RecordingFormatFlags, i16);
+
+#[cfg(test)]
+mod video_commands_tests {
+    use super::*;
+    use crate::commands::assert_every_parameter;
+
+    /// One packet per parameter of the video group, 1.0 to 1.16.
+    fn every_parameter() -> Vec<(Vec<u8>, VideoCommand)> {
+        vec![
+            (
+                vec![0x01, 0x00, 0x01, 0x00, 0x18, 0x00, 0x03, 0x00, 0x00],
+                VideoCommand::VideoMode {
+                    operation: Operation::Assign,
+                    data: VideoModeData {
+                        frame_rate: 24,
+                        m_rate: 0,
+                        dimensions: VideoDimensions::Hd1080.into(),
+                        interlaced: 0,
+                        color_space: ColorSpace::Yuv.into(),
+                    },
+                },
+            ),
+            (
+                vec![0x01, 0x01, 0x01, 0x00, 0x04],
+                VideoCommand::GainUpToCamera49 {
+                    operation: Operation::Assign,
+                    data: 4,
+                },
+            ),
+            (
+                vec![0x01, 0x02, 0x02, 0x00, 0x88, 0x13, 0xf6, 0xff],
+                VideoCommand::ManualWhiteBalance {
+                    operation: Operation::Assign,
+                    data: ManualWhiteBalanceData {
+                        color_temp: 5000,
+                        tint: -10,
+                    },
+                },
+            ),
+            (vec![0x01, 0x03, 0x00, 0x00], VideoCommand::SetAutoWB),
+            (vec![0x01, 0x04, 0x00, 0x00], VideoCommand::RestoreAutoWB),
+            (
+                vec![0x01, 0x05, 0x03, 0x00, 0x10, 0x27, 0x00, 0x00],
+                VideoCommand::ExposureUS {
+                    operation: Operation::Assign,
+                    data: 10000,
+                },
+            ),
+            (
+                vec![0x01, 0x06, 0x02, 0x01, 0x01, 0x00],
+                VideoCommand::ExposureOrdinal {
+                    operation: Operation::Increment,
+                    data: 1,
+                },
+            ),
+            (
+                vec![0x01, 0x07, 0x01, 0x00, 0x01],
+                VideoCommand::DynamicRageMode {
+                    operation: Operation::Assign,
+                    data: DynamicRangeMode::Video.into(),
+                },
+            ),
+            (
+                vec![0x01, 0x08, 0x01, 0x00, 0x02],
+                VideoCommand::VideoSharpeningLevel {
+                    operation: Operation::Assign,
+                    data: SharpeningLevel::Medium.into(),
+                },
+            ),
+            (
+                vec![
+                    0x01, 0x09, 0x02, 0x00, 0x18, 0x00, 0x18, 0x00, 0x80, 0x07, 0x38, 0x04, 0x00,
+                    0x00,
+                ],
+                VideoCommand::RecordingFormat {
+                    operation: Operation::Assign,
+                    data: RecordingFormatData {
+                        file_frame_rate: 24,
+                        sensor_frame_rate: 24,
+                        frame_width: 1920,
+                        frame_height: 1080,
+                        flags: 0,
+                    },
+                },
+            ),
+            (
+                vec![0x01, 0x0a, 0x01, 0x00, 0x03],
+                VideoCommand::SetAutoExposureMode {
+                    operation: Operation::Assign,
+                    data: AutoExposureMode::IrisShutter.into(),
+                },
+            ),
+            (
+                vec![0x01, 0x0b, 0x03, 0x00, 0x50, 0x46, 0x00, 0x00],
+                VideoCommand::ShutterAngle {
+                    operation: Operation::Assign,
+                    data: 18000,
+                },
+            ),
+            (
+                vec![0x01, 0x0c, 0x03, 0x00, 0x32, 0x00, 0x00, 0x00],
+                VideoCommand::ShutterSpeed {
+                    operation: Operation::Assign,
+                    data: 50,
+                },
+            ),
+            (
+                vec![0x01, 0x0d, 0x01, 0x01, 0xfa],
+                VideoCommand::Gain {
+                    operation: Operation::Increment,
+                    data: -6,
+                },
+            ),
+            (
+                vec![0x01, 0x0e, 0x03, 0x00, 0x20, 0x03, 0x00, 0x00],
+                VideoCommand::ISO {
+                    operation: Operation::Assign,
+                    data: 800,
+                },
+            ),
+            (
+                vec![0x01, 0x0f, 0x01, 0x00, 0x02, 0x01],
+                VideoCommand::DisplayLUT {
+                    operation: Operation::Assign,
+                    data: DisplayLUTData {
+                        selected: DisplayLut::FilmToVideo.into(),
+                        enabled: 1,
+                    },
+                },
+            ),
+            #[cfg(not(feature = "ignore-nd-filter"))]
+            (
+                vec![0x01, 0x10, 0x80, 0x00, 0x00, 0x10, 0x00, 0x00],
+                VideoCommand::NDFilterStop {
+                    operation: Operation::Assign,
+                    data: NDFilterStopData {
+                        stop: FixedPointDecimal { raw_val: 0x1000 },
+                        display_mode: FixedPointDecimal { raw_val: 0 },
+                    },
+                },
+            ),
+            #[cfg(feature = "ignore-nd-filter")]
+            (
+                vec![0x01, 0x10, 0x80, 0x00, 0x00, 0x00],
+                VideoCommand::NDFilterStop,
+            ),
+        ]
+    }
+
+    #[test]
+    fn every_parameter_parses_and_round_trips() {
+        assert_every_parameter(
+            every_parameter(),
+            parse_command,
+            VideoCommand::to_command_bytes,
+        );
+    }
 }
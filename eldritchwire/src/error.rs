@@ -1,14 +1,64 @@
-use std::fmt;
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::fmt;
+
+/// Where in a frame packet a command failed, `offset` is the index of the command header and
+/// `index` the index of the byte the error is about, e.g. the parameter or a padding byte.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ErrorContext {
+    pub offset: usize,
+    pub index: usize,
+    pub device_id: u8,
+    pub category: Option<u8>,
+    pub parameter: Option<u8>,
+}
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum EldritchError {
     DataOutOfBounds,
     EndOfPacket,
-    InvalidCommandData { message: String, data: Vec<u8> },
-    InvalidDataType { expected: String, command: String },
+    InFrame {
+        context: ErrorContext,
+        error: Box<EldritchError>,
+    },
+    InvalidCommandData {
+        message: &'static str,
+        data: Vec<u8>,
+    },
+    /// The command was sent with another data type than the one its parameter is declared with.
+    InvalidDataType {
+        data_type: u8,
+        expected: u8,
+        raw: Vec<u8>,
+    },
     InvalidHeader,
+    /// The operation can't be used with the data type, e.g. incrementing a string.
+    InvalidOperation {
+        operation: u8,
+        data_type: u8,
+        raw: Vec<u8>,
+    },
+    InvalidText {
+        message: &'static str,
+        text: String,
+    },
+    NonZeroPadding {
+        index: usize,
+    },
     PacketToLarge,
-    PaddingViolation(String),
+    PaddingViolation(&'static str),
+    UnknownCategory {
+        category: u8,
+        raw: Vec<u8>,
+    },
+    UnknownDataType {
+        data_type: u8,
+        raw: Vec<u8>,
+    },
+    UnknownParameter {
+        category: u8,
+        parameter: u8,
+        raw: Vec<u8>,
+    },
 }
 
 impl fmt::Display for EldritchError {
@@ -22,10 +72,56 @@ impl fmt::Display for EldritchError {
                 write!(f, "Attempting to retrieve more data at the end of packet")
             }
             EldritchError::PacketToLarge => write!(f, "Blanking packet is larger then 255 bytes"),
+            EldritchError::InFrame { context, error } => {
+                write!(f, "{error} (offset: {}, device id: {}", context.offset, context.device_id)?;
+                if let Some(category) = context.category {
+                    write!(f, ", category: {category:#04x}")?;
+                }
+                if let Some(parameter) = context.parameter {
+                    write!(f, ", parameter: {parameter:#04x}")?;
+                }
+                write!(f, ")")
+            }
             EldritchError::InvalidCommandData{ message, data } => write!(f, "Invalid Command Data. \n {message}, data:\n\t{data:?}"),
-            EldritchError::InvalidDataType{ expected, command  }=> write!(f, "Data type provided does not match command. Command: {command} Expected: {expected}"),
+            EldritchError::InvalidDataType { data_type, expected, raw } => write!(f, "Data type {data_type:#04x} does not match the command, expected {expected:#04x}, data:\n\t{raw:?}"),
             EldritchError::InvalidHeader => write!(f, "Command Header is invlid"),
+            EldritchError::InvalidOperation { operation, data_type, raw } => write!(f, "Operation {operation:#04x} is not supported for data type {data_type:#04x}, data:\n\t{raw:?}"),
+            EldritchError::InvalidText { message, text } => write!(f, "Invalid command text. {message}: {text}"),
+            EldritchError::NonZeroPadding { index } => write!(f, "Padding byte at index: {index} is not 0x00"),
             EldritchError::PaddingViolation(msg) => write!(f, "{}", msg),
+            EldritchError::UnknownCategory { category, raw } => write!(f, "Unknown command category: {category:#04x}, data:\n\t{raw:?}"),
+            EldritchError::UnknownDataType { data_type, raw } => write!(f, "Unknown data type: {data_type:#04x}, data:\n\t{raw:?}"),
+            EldritchError::UnknownParameter { category, parameter, raw } => write!(f, "Unknown parameter: {parameter:#04x} for command category: {category:#04x}, data:\n\t{raw:?}"),
+        }
+    }
+}
+
+impl core::error::Error for EldritchError {}
+
+impl EldritchError {
+    /// Context for errors that came out of a frame packet, `None` for a bare error.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            EldritchError::InFrame { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// The error without the frame context wrapped around it.
+    pub fn root_cause(&self) -> &EldritchError {
+        match self {
+            EldritchError::InFrame { error, .. } => error.root_cause(),
+            error => error,
+        }
+    }
+
+    pub(crate) fn in_frame(self, context: ErrorContext) -> Self {
+        match self {
+            EldritchError::InFrame { .. } => self,
+            error => EldritchError::InFrame {
+                context,
+                error: Box::new(error),
+            },
         }
     }
 }
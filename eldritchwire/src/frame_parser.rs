@@ -0,0 +1,373 @@
+use crate::{
+    calculate_padding_length,
+    commands::{self, CommandData},
+    error::ErrorContext,
+    verify_padding, AddressedCommand, EldritchError, PacketData,
+};
+
+/// A command in a frame packet that has not been parsed yet, it borrows the packet buffer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AddressedCommandData<'a> {
+    /// Index of the command header in the frame packet.
+    pub offset: usize,
+    pub device_id: u8,
+    pub command_data: CommandData<'a>,
+}
+
+impl AddressedCommandData<'_> {
+    pub fn to_addressed_command(&self) -> Result<AddressedCommand, EldritchError> {
+        Ok(AddressedCommand {
+            device_id: self.device_id,
+            command: commands::parse_command(self.command_data.raw()).map_err(|error| {
+                let index = error_index(&error, self.offset, self.command_data.raw().len());
+                error.in_frame(ErrorContext {
+                    offset: self.offset,
+                    index,
+                    device_id: self.device_id,
+                    category: Some(*self.command_data.category()),
+                    parameter: Some(*self.command_data.parameter()),
+                })
+            })?,
+        })
+    }
+}
+
+/// Lazily walks the commands of a frame packet without copying the buffer.
+///
+/// Iterating yields owned [`AddressedCommand`]s, use [`FrameParser::next_command_data`] to get
+/// at the borrowed [`CommandData`] instead. Iteration stops after the first error and drops a last
+/// command that is cut off by the end of the packet.
+#[derive(Debug)]
+pub struct FrameParser<'a> {
+    packet: PacketData<'a>,
+    finished: bool,
+    /// Bad padding after the command handed out last, reported by the next call.
+    padding_error: Option<EldritchError>,
+}
+
+impl<'a> FrameParser<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, EldritchError> {
+        Ok(Self {
+            packet: PacketData::new(data)?,
+            finished: false,
+            padding_error: None,
+        })
+    }
+
+    /// Hands out the next command, after a bad one it carries on with the command that follows.
+    ///
+    /// Bad padding is reported after the command it follows. Only a bad header ends the packet
+    /// early, as does a command cut off by the end of the packet, which is reported as
+    /// [`EldritchError::EndOfPacket`].
+    pub fn next_command_data(&mut self) -> Option<Result<AddressedCommandData<'a>, EldritchError>> {
+        if let Some(error) = self.padding_error.take() {
+            return Some(Err(error));
+        }
+        if self.finished || !self.packet.has_data() {
+            return None;
+        }
+
+        let offset = usize::from(self.packet.cursor);
+        Some(self.parse_next(offset).map_err(|error| {
+            let context = error_context(self.packet.data, offset, &error);
+            error.in_frame(context)
+        }))
+    }
+
+    fn parse_next(&mut self, offset: usize) -> Result<AddressedCommandData<'a>, EldritchError> {
+        let header = self
+            .packet
+            .parse_header()
+            .inspect_err(|_| self.finished = true)?;
+        let command = self
+            .packet
+            .get_slice(header.command_length)
+            .inspect_err(|_| self.finished = true)?;
+
+        match self
+            .packet
+            .get_slice(calculate_padding_length(header.command_length))
+        {
+            Ok(padding) => {
+                if let Err(error) = verify_padding(padding, header.command_length) {
+                    let context = error_context(self.packet.data, offset, &error);
+                    self.padding_error = Some(error.in_frame(context));
+                }
+            }
+            // The last command of a packet is allowed to be missing its padding.
+            Err(_) => self.finished = true,
+        };
+
+        Ok(AddressedCommandData {
+            offset,
+            device_id: header.device_id,
+            command_data: CommandData::new(command)?,
+        })
+    }
+}
+
+/// Context for `error` in the command with its header at `offset`, taken from the raw packet so
+/// it works for commands whose header or data didn't parse.
+fn error_context(packet: &[u8], offset: usize, error: &EldritchError) -> ErrorContext {
+    let data = &packet[offset..];
+    let command_length = data.get(1).copied().unwrap_or_default();
+    let mut command = data
+        .get(4..)
+        .unwrap_or_default()
+        .iter()
+        .take(usize::from(command_length))
+        .copied();
+    ErrorContext {
+        offset,
+        index: error_index(error, offset, usize::from(command_length)),
+        device_id: data[0],
+        category: command.next(),
+        parameter: command.next(),
+    }
+}
+
+/// Index in the frame packet of the byte `error` is about, for the command with its header at
+/// `offset`. Errors about the data itself point at the first data byte.
+fn error_index(error: &EldritchError, offset: usize, command_length: usize) -> usize {
+    let command = offset + 4;
+    match error {
+        EldritchError::InvalidHeader => offset + 3,
+        // The command length in the header runs past the end of the packet.
+        EldritchError::EndOfPacket => offset + 1,
+        EldritchError::UnknownCategory { .. } => command,
+        EldritchError::UnknownParameter { .. } => command + 1,
+        EldritchError::UnknownDataType { .. } | EldritchError::InvalidDataType { .. } => {
+            command + 2
+        }
+        EldritchError::InvalidOperation { .. } => command + 3,
+        EldritchError::NonZeroPadding { index } => command + command_length + index,
+        EldritchError::PaddingViolation(_) => command + command_length,
+        EldritchError::InvalidCommandData { data, .. } if data.len() < 4 => command,
+        _ => command + 4,
+    }
+}
+
+impl Iterator for FrameParser<'_> {
+    type Item = Result<AddressedCommand, EldritchError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let command = match self.next_command_data()? {
+            Err(error) if *error.root_cause() == EldritchError::EndOfPacket => return None,
+            command => command.and_then(|command| command.to_addressed_command()),
+        };
+        if command.is_err() {
+            self.finished = true;
+        }
+        Some(command)
+    }
+}
+
+#[cfg(test)]
+mod frame_parser_test {
+    use super::*;
+    use crate::{
+        commands::{lens_commands::LensCommand, Command},
+        FixedPointDecimal, Operation,
+    };
+
+    #[test]
+    fn next_command_data_borrows_packet() {
+        let packet_data = [
+            0x00, 0x06, 0x00, 0x00, // Header
+            0x00, 0x00, 0x80, 0x01, 0x33, 0x01, // Command
+            0x00, 0x00, // Padding
+            0x04, 0x04, 0x00, 0x00, // Header
+            0x00, 0x01, 0x00, 0x00, // Command
+        ];
+        let mut parser = FrameParser::new(&packet_data).expect("Packet is not to large");
+
+        let command = parser
+            .next_command_data()
+            .expect("Packet has a first command")
+            .expect("Known good command");
+        assert_eq!(command.device_id, 0);
+        assert_eq!(command.command_data.raw(), &packet_data[4..10]);
+        assert!(std::ptr::eq(
+            command.command_data.raw().as_ptr(),
+            packet_data[4..].as_ptr()
+        ));
+
+        let command = parser
+            .next_command_data()
+            .expect("Packet has a second command")
+            .expect("Known good command");
+        assert_eq!(command.device_id, 4);
+        assert_eq!(command.command_data.parameter(), &0x01);
+
+        assert_eq!(parser.next_command_data(), None);
+    }
+
+    #[test]
+    fn iterate_owned_commands() {
+        let packet_data = [
+            0x00, 0x06, 0x00, 0x00, // Header
+            0x00, 0x00, 0x80, 0x01, 0x33, 0x01, // Command
+            0x00, 0x00, // Padding
+            0xff, 0x04, 0x00, 0x00, // Header
+            0x00, 0x01, 0x00, 0x00, // Command
+        ];
+        let commands: Result<Vec<_>, _> = FrameParser::new(&packet_data)
+            .expect("Packet is not to large")
+            .collect();
+
+        assert_eq!(
+            commands,
+            Ok(vec![
+                AddressedCommand {
+                    device_id: 0,
+                    command: Command::Lens(LensCommand::Focus {
+                        operation: Operation::Increment,
+                        data: FixedPointDecimal {
+                            raw_val: 0x0133u16 as i16
+                        }
+                    })
+                },
+                AddressedCommand {
+                    device_id: 255,
+                    command: Command::Lens(LensCommand::InstantaneousAutoFocus),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn iteration_stops_after_error() {
+        let packet_data = [
+            0x00, 0x05, 0x00, 0x00, // Header
+            0x7f, 0x00, 0x00, 0x00, 0x01, // Unknown category
+            0x00, 0x00, 0x00, // Padding
+            0x00, 0x04, 0x00, 0x00, // Header
+            0x00, 0x01, 0x00, 0x00, // Command
+        ];
+        let mut parser = FrameParser::new(&packet_data).expect("Packet is not to large");
+
+        let error = parser
+            .next()
+            .expect("Packet has a command")
+            .expect_err("Unknown category");
+        assert!(matches!(
+            error.root_cause(),
+            EldritchError::UnknownCategory { .. }
+        ));
+        assert_eq!(parser.next(), None);
+    }
+
+    #[test]
+    fn command_data_stays_raw_until_parsed() {
+        // Unknown categories are only an error once the command is turned into a `Command`.
+        let packet_data = [
+            0x00, 0x05, 0x00, 0x00, // Header
+            0x7f, 0x00, 0x00, 0x00, 0x01, // Unknown category
+            0x00, 0x00, 0x00, // Padding
+        ];
+        let mut parser = FrameParser::new(&packet_data).expect("Packet is not to large");
+
+        let command = parser
+            .next_command_data()
+            .expect("Packet has a command")
+            .expect("Command data is well formed");
+        assert_eq!(command.command_data.category(), &0x7f);
+        assert!(command.to_addressed_command().is_err());
+    }
+
+    #[test]
+    fn error_context_for_bad_command() {
+        let packet_data = [
+            0x00, 0x04, 0x00, 0x00, // Header
+            0x00, 0x01, 0x00, 0x00, // Command
+            0x03, 0x05, 0x00, 0x00, // Header
+            0x00, 0x7f, 0x00, 0x00, 0x01, // Unknown parameter
+            0x00, 0x00, 0x00, // Padding
+        ];
+        let error = FrameParser::new(&packet_data)
+            .expect("Packet is not to large")
+            .collect::<Result<Vec<_>, _>>()
+            .expect_err("Second command has an unknown parameter");
+
+        assert_eq!(
+            error.context(),
+            Some(&ErrorContext {
+                offset: 8,
+                index: 13,
+                device_id: 3,
+                category: Some(0x00),
+                parameter: Some(0x7f),
+            })
+        );
+        assert_eq!(
+            error.to_string(),
+            "Unknown parameter: 0x7f for command category: 0x00, data:\n\t[0, 127, 0, 0, 1] \
+             (offset: 8, device id: 3, category: 0x00, parameter: 0x7f)"
+        );
+    }
+
+    #[test]
+    fn error_context_for_bad_header() {
+        let packet_data = [
+            0x00, 0x04, 0x00, 0x00, // Header
+            0x00, 0x01, 0x00, 0x00, // Command
+            0x02, 0x04, 0x00, 0x01, // Bad reserved byte
+            0x00, 0x01, 0x00, 0x00, // Command
+        ];
+        let mut parser = FrameParser::new(&packet_data).expect("Packet is not to large");
+        parser.next();
+
+        let error = parser
+            .next_command_data()
+            .expect("Packet has a second header")
+            .expect_err("Header is invalid");
+        assert_eq!(
+            error,
+            EldritchError::InvalidHeader.in_frame(ErrorContext {
+                offset: 8,
+                index: 11,
+                device_id: 2,
+                category: Some(0x00),
+                parameter: Some(0x01),
+            })
+        );
+        assert_eq!(error.root_cause(), &EldritchError::InvalidHeader);
+    }
+
+    #[test]
+    fn error_index_points_at_bad_byte() {
+        let index = |packet_data: &[u8]| {
+            FrameParser::new(packet_data)
+                .expect("Packet is not to large")
+                .collect::<Result<Vec<_>, _>>()
+                .expect_err("Packet has a bad command")
+                .context()
+                .map(|context| context.index)
+        };
+
+        // Focus sent as a signed byte instead of fixed point.
+        assert_eq!(
+            index(&[0x00, 0x06, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x04, 0x00, 0x00]),
+            Some(6)
+        );
+        // Stabilisation on, followed by a non zero padding byte.
+        assert_eq!(
+            index(&[0x00, 0x05, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x01, 0x00, 0xff, 0x00]),
+            Some(10)
+        );
+        // Phantom power with an operation that is neither assign nor toggle.
+        assert_eq!(
+            index(&[0x00, 0x05, 0x00, 0x00, 0x02, 0x06, 0x00, 0x02, 0x01, 0x00, 0x00, 0x00]),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn packet_too_large() {
+        let packet_data = [0x00; 256];
+        assert_eq!(
+            FrameParser::new(&packet_data).map(|_| ()),
+            Err(EldritchError::PacketToLarge)
+        );
+    }
+}
@@ -1,15 +1,63 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+mod changes;
 pub mod commands;
 mod error;
+mod frame_parser;
+#[cfg(feature = "preset")]
+mod preset;
+mod state;
+mod tally;
+mod text;
+use alloc::vec::Vec;
+pub use changes::{ChangeFilter, ChangeNotifier, ParameterId, StateChange};
 use commands::Command;
-use error::EldritchError;
-use std::fmt::Debug;
+use core::fmt::Debug;
+pub use error::{EldritchError, ErrorContext};
+pub use frame_parser::{AddressedCommandData, FrameParser};
+#[cfg(feature = "preset")]
+pub use preset::{Preset, PresetError, PRESET_VERSION};
+pub use state::{diff, CameraRegistry, CameraState, BROADCAST_DEVICE_ID};
+pub use tally::{CameraTally, TallyFrame};
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddressedCommand {
     pub device_id: u8,
     pub command: Command,
 }
 
+/// A command that failed to parse, `context` says where in the frame packet it is.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandParseError {
+    pub context: ErrorContext,
+    pub error: EldritchError,
+}
+
+impl CommandParseError {
+    /// Splits an error out of [`FrameParser`], which wraps every error about a command in
+    /// [`EldritchError::InFrame`].
+    fn from_frame_error(error: EldritchError) -> Self {
+        match error {
+            EldritchError::InFrame { context, error } => Self {
+                context,
+                error: *error,
+            },
+            error => unreachable!("FrameParser returned an error without context: {error:?}"),
+        }
+    }
+}
+
+/// Result of [`parse_frame_packet_lenient`], every command that could be decoded alongside
+/// the errors for those that couldn't.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParsedFrame {
+    pub commands: Vec<AddressedCommand>,
+    pub errors: Vec<CommandParseError>,
+}
+
 #[derive(Clone, PartialEq, PartialOrd)]
 pub struct FixedPointDecimal {
     raw_val: i16,
@@ -17,11 +65,38 @@ pub struct FixedPointDecimal {
 
 impl FixedPointDecimal {
     pub fn get_real_val(&self) -> f32 {
-        f32::from(self.raw_val) / 2_f32.powi(11)
+        f32::from(self.raw_val) / 2048.0
     }
 
     pub fn get_rounded_val(&self) -> f32 {
-        (self.get_real_val() * 100.0).round() / 100.0
+        // The scaled value is at most +/-1600 so it fits in an i32.
+        round_half_away(self.get_real_val() * 100.0) as f32 / 100.0
+    }
+
+    /// The real value with as few decimals as still map back to the same fixed point value,
+    /// `0.15` rather than `0.14990234`.
+    pub fn get_shortest_val(&self) -> f32 {
+        let mut scale = 1.0;
+        loop {
+            // The scaled value is at most +/-160000, so it fits in an i32.
+            let rounded = round_half_away(self.get_real_val() * scale) as f32 / scale;
+            if scale >= 10_000.0 || Self::from_real_val(rounded).as_ref() == Ok(self) {
+                return rounded;
+            }
+            scale *= 10.0;
+        }
+    }
+
+    /// Nearest fixed point value, `DataOutOfBounds` outside of -16.0 to 15.9995.
+    pub fn from_real_val(real_val: f32) -> Result<Self, EldritchError> {
+        if real_val.is_nan() {
+            return Err(EldritchError::DataOutOfBounds);
+        }
+        // Casting to an i32 saturates, so anything that doesn't fit an i16 is rejected below.
+        let rounded = round_half_away(real_val * 2048.0);
+        Ok(Self {
+            raw_val: i16::try_from(rounded).map_err(|_| EldritchError::DataOutOfBounds)?,
+        })
     }
 
     pub fn from_data(data: &[u8; 2]) -> Self {
@@ -30,10 +105,39 @@ impl FixedPointDecimal {
             raw_val: u16::from_le_bytes(*data) as i16,
         }
     }
+
+    pub fn to_data(&self) -> [u8; 2] {
+        self.raw_val.to_le_bytes()
+    }
+
+    pub fn saturating_add(&self, rhs: &Self) -> Self {
+        Self {
+            raw_val: self.raw_val.saturating_add(rhs.raw_val),
+        }
+    }
+
+    /// Clamps to real value bounds, as declared in a command's `bounds` attribute.
+    pub fn clamp_real(&self, lower: f32, upper: f32) -> Self {
+        Self {
+            raw_val: self
+                .raw_val
+                .clamp((lower * 2048.0) as i16, (upper * 2048.0) as i16),
+        }
+    }
+}
+
+/// Rounds half way cases away from zero like `f32::round`, which needs std. Saturates at the
+/// bounds of an i32.
+fn round_half_away(val: f32) -> i32 {
+    if val < 0.0 {
+        (val - 0.5) as i32
+    } else {
+        (val + 0.5) as i32
+    }
 }
 
 impl Debug for FixedPointDecimal {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "FixedPointDecimal {{ raw_val: {}, real_val: {} }}",
@@ -43,6 +147,26 @@ impl Debug for FixedPointDecimal {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for FixedPointDecimal {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f32(self.get_shortest_val())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FixedPointDecimal {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let real_val = f32::deserialize(deserializer)?;
+        Self::from_real_val(real_val).map_err(|_| {
+            serde::de::Error::invalid_value(
+                serde::de::Unexpected::Float(f64::from(real_val)),
+                &"a fixed point value between -16.0 and 15.9995",
+            )
+        })
+    }
+}
+
 impl PartialEq<f32> for FixedPointDecimal {
     fn eq(&self, other: &f32) -> bool {
         &self.get_real_val() == other
@@ -50,19 +174,20 @@ impl PartialEq<f32> for FixedPointDecimal {
 }
 
 impl PartialOrd<f32> for FixedPointDecimal {
-    fn partial_cmp(&self, other: &f32) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &f32) -> Option<core::cmp::Ordering> {
         let real_val = self.get_real_val();
         if &real_val == other {
-            Some(std::cmp::Ordering::Equal)
+            Some(core::cmp::Ordering::Equal)
         } else if &real_val < other {
-            Some(std::cmp::Ordering::Less)
+            Some(core::cmp::Ordering::Less)
         } else {
-            Some(std::cmp::Ordering::Greater)
+            Some(core::cmp::Ordering::Greater)
         }
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Operation {
     Assign,
     Increment,
@@ -76,14 +201,20 @@ struct CommandHeader {
     command_id: u8,
 }
 
+impl CommandHeader {
+    fn to_bytes(&self) -> [u8; 4] {
+        [self.device_id, self.command_length, self.command_id, 0x00]
+    }
+}
+
 #[derive(Debug, PartialEq)]
-struct PacketData {
-    data: Vec<u8>,
+struct PacketData<'a> {
+    data: &'a [u8],
     cursor: u8,
 }
 
-impl PacketData {
-    pub fn new(packet_data: Vec<u8>) -> Result<Self, EldritchError> {
+impl<'a> PacketData<'a> {
+    pub fn new(packet_data: &'a [u8]) -> Result<Self, EldritchError> {
         if packet_data.len() > 255 {
             Err(EldritchError::PacketToLarge)
         } else {
@@ -95,20 +226,18 @@ impl PacketData {
     }
 
     pub fn parse_header(&mut self) -> Result<CommandHeader, EldritchError> {
-        let device_id = self
-            .data
-            .get(self.cursor as usize)
-            .ok_or(EldritchError::InvalidHeader)?;
+        let cursor = usize::from(self.cursor);
+        let device_id = self.data.get(cursor).ok_or(EldritchError::InvalidHeader)?;
         let command_length = self
             .data
-            .get((self.cursor + 1) as usize)
+            .get(cursor + 1)
             .ok_or(EldritchError::InvalidHeader)?;
         let command_id = self
             .data
-            .get((self.cursor + 2) as usize)
+            .get(cursor + 2)
             .ok_or(EldritchError::InvalidHeader)?;
 
-        let header = if let Some(reserved) = self.data.get((self.cursor + 3) as usize) {
+        let header = if let Some(reserved) = self.data.get(cursor + 3) {
             if *reserved == 0 {
                 Ok(CommandHeader {
                     device_id: *device_id,
@@ -121,7 +250,7 @@ impl PacketData {
         } else {
             Err(EldritchError::InvalidHeader)
         };
-        self.cursor += 4;
+        self.cursor = self.cursor.saturating_add(4);
         header
     }
 
@@ -129,44 +258,96 @@ impl PacketData {
         usize::from(self.cursor) < self.data.len()
     }
 
-    fn get_slice(&mut self, slice_len: u8) -> Result<&[u8], EldritchError> {
-        let new_cur = self.cursor + slice_len;
-        if usize::from(new_cur) > self.data.len() {
+    fn get_slice(&mut self, slice_len: u8) -> Result<&'a [u8], EldritchError> {
+        let new_cur = usize::from(self.cursor) + usize::from(slice_len);
+        if new_cur > self.data.len() {
             return Err(EldritchError::EndOfPacket);
         }
-        let slice_data = &self.data[usize::from(self.cursor)..usize::from(new_cur)];
-        self.cursor = new_cur;
+        let slice_data = &self.data[usize::from(self.cursor)..new_cur];
+        // Packet data is never longer then 255 bytes, so the cursor always fits.
+        self.cursor = new_cur as u8;
         Ok(slice_data)
     }
 }
 
 pub fn parse_frame_packet(data: Vec<u8>) -> Result<Vec<AddressedCommand>, EldritchError> {
-    let mut packet = PacketData::new(data)?;
-    let mut commands: Vec<AddressedCommand> = Vec::new();
-
-    while packet.has_data() {
-        let header = packet.parse_header()?;
-        // println!("Command Header: {header:?}");
-        match packet.get_slice(header.command_length) {
-            Ok(command_data) => {
-                // println!("Command Data: {command_data:?}");
-                commands.push(AddressedCommand {
-                    device_id: header.device_id,
-                    command: commands::parse_command(command_data)?,
-                });
-            }
-            Err(EldritchError::EndOfPacket) => break,
-            Err(err) => Err(err)?,
-        };
+    FrameParser::new(&data)?.collect()
+}
 
-        match packet.get_slice(calculate_padding_length(header.command_length)) {
-            Ok(padding) => verify_padding(padding, header.command_length)?,
-            Err(EldritchError::EndOfPacket) => break,
-            Err(err) => Err(err)?,
-        };
+/// Best effort version of [`parse_frame_packet`].
+///
+/// A bad command is recorded in [`ParsedFrame::errors`] and parsing picks back up at the next
+/// command boundary given by the header length. Only a bad header or a command cut off by the end
+/// of the packet stops parsing early, since there is no next command to find.
+pub fn parse_frame_packet_lenient(data: Vec<u8>) -> Result<ParsedFrame, EldritchError> {
+    let mut parser = FrameParser::new(&data)?;
+    let mut frame = ParsedFrame::default();
+
+    while let Some(command) = parser.next_command_data() {
+        match command.and_then(|command| command.to_addressed_command()) {
+            Ok(command) => frame.commands.push(command),
+            Err(error) => frame
+                .errors
+                .push(CommandParseError::from_frame_error(error)),
+        }
     }
 
-    Ok(commands)
+    Ok(frame)
+}
+
+pub fn encode_frame_packet(commands: &[AddressedCommand]) -> Result<Vec<u8>, EldritchError> {
+    let mut packet: Vec<u8> = Vec::new();
+
+    for command in commands {
+        packet.extend_from_slice(&encode_addressed_command(command)?);
+
+        if packet.len() > 255 {
+            return Err(EldritchError::PacketToLarge);
+        }
+    }
+
+    Ok(packet)
+}
+
+/// Splits commands over as few frame packets as the 255 byte limit allows, keeping their order.
+pub fn encode_frame_packets(commands: &[AddressedCommand]) -> Result<Vec<Vec<u8>>, EldritchError> {
+    let mut packets: Vec<Vec<u8>> = Vec::new();
+    let mut packet: Vec<u8> = Vec::new();
+
+    for command in commands {
+        let command_data = encode_addressed_command(command)?;
+        if command_data.len() > 255 {
+            return Err(EldritchError::PacketToLarge);
+        }
+        if packet.len() + command_data.len() > 255 {
+            packets.push(core::mem::take(&mut packet));
+        }
+        packet.extend_from_slice(&command_data);
+    }
+
+    if !packet.is_empty() {
+        packets.push(packet);
+    }
+    Ok(packets)
+}
+
+/// Header, command data and padding of a single command.
+fn encode_addressed_command(command: &AddressedCommand) -> Result<Vec<u8>, EldritchError> {
+    let command_data = commands::encode_command(&command.command)?;
+    let header = CommandHeader {
+        device_id: command.device_id,
+        command_length: u8::try_from(command_data.len())
+            .map_err(|_| EldritchError::PacketToLarge)?,
+        command_id: 0,
+    };
+
+    let mut data = Vec::from(header.to_bytes());
+    data.extend_from_slice(&command_data);
+    data.resize(
+        data.len() + usize::from(calculate_padding_length(header.command_length)),
+        0x00,
+    );
+    Ok(data)
 }
 
 fn calculate_padding_length(command_length: u8) -> u8 {
@@ -179,9 +360,9 @@ fn calculate_padding_length(command_length: u8) -> u8 {
 
 fn verify_padding(padding: &[u8], command_length: u8) -> Result<(), EldritchError> {
     if !(padding.len() + usize::from(command_length)).is_multiple_of(4) {
-        return Err(EldritchError::PaddingViolation(String::from(
+        return Err(EldritchError::PaddingViolation(
             "Padding length is incorrect",
-        )));
+        ));
     }
 
     let mut padding_errors: Vec<Result<(), EldritchError>> = padding
@@ -189,9 +370,7 @@ fn verify_padding(padding: &[u8], command_length: u8) -> Result<(), EldritchErro
         .enumerate()
         .map(|(idx, byte)| {
             if *byte != 0x00 {
-                Err(EldritchError::PaddingViolation(format!(
-                    "Padding byte at index: {idx} is not 0x00",
-                )))
+                Err(EldritchError::NonZeroPadding { index: idx })
             } else {
                 Ok(())
             }
@@ -225,6 +404,193 @@ mod fixed_point_test {
         let float_value = minus_point_three.get_rounded_val();
         assert_eq!(float_value, -0.3_f32);
     }
+
+    #[test]
+    fn fpd_from_real_val() {
+        assert_eq!(
+            FixedPointDecimal::from_real_val(0.15),
+            Ok(FixedPointDecimal { raw_val: 0x0133 })
+        );
+        assert_eq!(
+            FixedPointDecimal::from_real_val(-16.0),
+            Ok(FixedPointDecimal { raw_val: i16::MIN })
+        );
+        assert_eq!(
+            FixedPointDecimal::from_real_val(15.9995),
+            Ok(FixedPointDecimal { raw_val: i16::MAX })
+        );
+    }
+
+    #[test]
+    fn fpd_from_real_val_out_of_range() {
+        for real_val in [16.0, -16.001, f32::NAN, f32::INFINITY] {
+            assert_eq!(
+                FixedPointDecimal::from_real_val(real_val),
+                Err(EldritchError::DataOutOfBounds)
+            );
+        }
+    }
+
+    #[test]
+    fn fpd_shortest_val() {
+        assert_eq!(
+            FixedPointDecimal { raw_val: 0x0133 }.get_shortest_val(),
+            0.15
+        );
+        assert_eq!(
+            FixedPointDecimal {
+                raw_val: 0xfd9au16 as i16
+            }
+            .get_shortest_val(),
+            -0.3
+        );
+        assert_eq!(
+            FixedPointDecimal { raw_val: 0x0800 }.get_shortest_val(),
+            1.0
+        );
+        assert_eq!(FixedPointDecimal { raw_val: 1 }.get_shortest_val(), 0.0005);
+
+        for raw_val in i16::MIN..=i16::MAX {
+            let fpd = FixedPointDecimal { raw_val };
+            assert_eq!(
+                FixedPointDecimal::from_real_val(fpd.get_shortest_val()),
+                Ok(fpd)
+            );
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_test {
+    use super::*;
+    use crate::commands::{
+        lens_commands::LensCommand, metadata_commands::SceneLocation, output_commands::GridStyle,
+    };
+
+    #[test]
+    fn addressed_command_json() {
+        let command = AddressedCommand {
+            device_id: 4,
+            command: Command::Lens(LensCommand::Focus {
+                operation: Operation::Assign,
+                data: FixedPointDecimal { raw_val: 0x0133 },
+            }),
+        };
+        let json =
+            r#"{"device_id":4,"command":{"Lens":{"Focus":{"operation":"Assign","data":0.15}}}}"#;
+
+        assert_eq!(serde_json::to_string(&command).unwrap(), json);
+        assert_eq!(
+            serde_json::from_str::<AddressedCommand>(json).unwrap(),
+            command
+        );
+    }
+
+    #[test]
+    fn void_command_json() {
+        let command = Command::Lens(LensCommand::InstantaneousAutoFocus);
+        assert_eq!(
+            serde_json::to_string(&command).unwrap(),
+            r#"{"Lens":"InstantaneousAutoFocus"}"#
+        );
+    }
+
+    #[test]
+    fn fixed_point_out_of_range_json() {
+        let error = serde_json::from_str::<FixedPointDecimal>("16.0").unwrap_err();
+        assert!(error.to_string().contains("between -16.0 and 15.9995"));
+    }
+
+    #[test]
+    fn protocol_codes_json() {
+        assert_eq!(
+            serde_json::to_string(&SceneLocation::Interior).unwrap(),
+            r#""Interior""#
+        );
+        assert_eq!(
+            serde_json::from_str::<GridStyle>(r#""THIRDS | CENTER_DOT""#).unwrap(),
+            GridStyle::THIRDS | GridStyle::CENTER_DOT
+        );
+    }
+}
+
+/// Frame packets shared by the parsing and encoding tests. The `REAL` packets were read off a
+/// shield one byte short, so each one is missing the last byte of its final command.
+#[cfg(test)]
+mod test_packets {
+    pub(crate) const LARGE_PACKET: [u8; 76] = [
+        0x04, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0xff, 0x05, 0x00, 0x00, 0x00, 0x06, 0x00,
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x04, 0x08, 0x00, 0x00, 0x01, 0x05, 0x03, 0x00, 0x10, 0x27,
+        0x00, 0x00, 0x04, 0x06, 0x00, 0x00, 0x04, 0x02, 0x80, 0x01, 0x33, 0x01, 0x00, 0x00, 0xff,
+        0x09, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x18, 0x01, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x04, 0x0c, 0x00, 0x00, 0x08, 0x01, 0x80, 0x01, 0x00, 0x00, 0x9a, 0xfd, 0x9a, 0xfd, 0x00,
+        0x00,
+    ];
+
+    pub(crate) const LONG_REAL_PACKET_ONE: [u8; 243] = [
+        0x01, 0x06, 0x00, 0x00, 0x00, 0x02, 0x80, 0x00, 0x8D, 0x1C, 0x00, 0x00, 0x01, 0x06, 0x00,
+        0x00, 0x01, 0x10, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x05, 0x00, 0x00, 0x01, 0x0D,
+        0x01, 0x00, 0x04, 0x00, 0x00, 0x00, 0x01, 0x05, 0x00, 0x00, 0x01, 0x01, 0x01, 0x00, 0x02,
+        0x00, 0x00, 0x00, 0x01, 0x08, 0x00, 0x00, 0x01, 0x02, 0x02, 0x00, 0x04, 0x10, 0x00, 0x00,
+        0x01, 0x08, 0x00, 0x00, 0x01, 0x05, 0x03, 0x00, 0x1B, 0x41, 0x00, 0x00, 0x01, 0x05, 0x00,
+        0x00, 0x01, 0x08, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x00, 0x00, 0x04, 0x04,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x0C, 0x00, 0x00, 0x08, 0x00, 0x80, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x45, 0x00, 0x01, 0x0C, 0x00, 0x00, 0x08, 0x01, 0x80, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x0C, 0x00, 0x00, 0x08, 0x02, 0x80,
+        0x00, 0x00, 0x08, 0x00, 0x08, 0x00, 0x08, 0xCE, 0x07, 0x01, 0x0C, 0x00, 0x00, 0x08, 0x03,
+        0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x08, 0x00, 0x00, 0x08,
+        0x04, 0x80, 0x00, 0x00, 0x04, 0x00, 0x08, 0x01, 0x06, 0x00, 0x00, 0x08, 0x05, 0x80, 0x00,
+        0x00, 0x08, 0x00, 0x00, 0x01, 0x08, 0x00, 0x00, 0x08, 0x06, 0x80, 0x00, 0x00, 0x00, 0x00,
+        0x08, 0x02, 0x06, 0x00, 0x00, 0x00, 0x02, 0x80, 0x00, 0x9B, 0x12, 0x00, 0x00, 0x02, 0x06,
+        0x00, 0x00, 0x01, 0x10, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x05, 0x00, 0x00, 0x01,
+        0x0D, 0x01, 0x00, 0x08, 0x00, 0x00, 0x00, 0x02, 0x05, 0x00, 0x00, 0x01, 0x01, 0x01, 0x00,
+        0x08, 0x00, 0x00,
+    ];
+
+    pub(crate) const LONG_REAL_PACKET_TWO: [u8; 251] = [
+        0x03, 0x0C, 0x00, 0x00, 0x08, 0x02, 0x80, 0x00, 0x00, 0x08, 0x00, 0x08, 0x00, 0x08, 0x00,
+        0x08, 0x03, 0x0C, 0x00, 0x00, 0x08, 0x03, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x03, 0x08, 0x00, 0x00, 0x08, 0x04, 0x80, 0x00, 0x00, 0x04, 0x00, 0x08, 0x03,
+        0x06, 0x00, 0x00, 0x08, 0x05, 0x80, 0x00, 0x00, 0x08, 0x00, 0x00, 0x03, 0x08, 0x00, 0x00,
+        0x08, 0x06, 0x80, 0x00, 0x00, 0x00, 0x00, 0x08, 0x04, 0x06, 0x00, 0x00, 0x00, 0x02, 0x80,
+        0x00, 0xF2, 0x15, 0x00, 0x00, 0x04, 0x06, 0x00, 0x00, 0x01, 0x10, 0x80, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x04, 0x05, 0x00, 0x00, 0x01, 0x0D, 0x01, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x04,
+        0x05, 0x00, 0x00, 0x01, 0x01, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x04, 0x08, 0x00, 0x00,
+        0x01, 0x02, 0x02, 0x00, 0x04, 0x10, 0x00, 0x00, 0x04, 0x08, 0x00, 0x00, 0x01, 0x05, 0x03,
+        0x00, 0x1B, 0x41, 0x00, 0x00, 0x04, 0x05, 0x00, 0x00, 0x01, 0x08, 0x01, 0x00, 0x01, 0x00,
+        0x00, 0x00, 0x04, 0x05, 0x00, 0x00, 0x04, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04,
+        0x0C, 0x00, 0x00, 0x08, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x04, 0x0C, 0x00, 0x00, 0x08, 0x01, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x04, 0x0C, 0x00, 0x00, 0x08, 0x02, 0x80, 0x00, 0x00, 0x08, 0x00, 0x08, 0x00, 0x08,
+        0x00, 0x08, 0x04, 0x0C, 0x00, 0x00, 0x08, 0x03, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x04, 0x08, 0x00, 0x00, 0x08, 0x04, 0x80, 0x00, 0x00, 0x04, 0x00, 0x08,
+        0x04, 0x06, 0x00, 0x00, 0x08, 0x05, 0x80, 0x00, 0x00, 0x08, 0x00,
+    ];
+
+    pub(crate) const LONG_REAL_PACKET_THREE: [u8; 247] = [
+        0x10, 0x05, 0x00, 0x00, 0x04, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x0C, 0x00,
+        0x00, 0x08, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x0C,
+        0x00, 0x00, 0x08, 0x01, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+        0x0C, 0x00, 0x00, 0x08, 0x02, 0x80, 0x00, 0x00, 0x08, 0x00, 0x08, 0x00, 0x08, 0x00, 0x08,
+        0x10, 0x0C, 0x00, 0x00, 0x08, 0x03, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x10, 0x08, 0x00, 0x00, 0x08, 0x04, 0x80, 0x00, 0x00, 0x04, 0x00, 0x08, 0x10, 0x06,
+        0x00, 0x00, 0x08, 0x05, 0x80, 0x00, 0x00, 0x08, 0x00, 0x00, 0x10, 0x08, 0x00, 0x00, 0x08,
+        0x06, 0x80, 0x00, 0x00, 0x00, 0x00, 0x08, 0x11, 0x06, 0x00, 0x00, 0x01, 0x10, 0x80, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x11, 0x05, 0x00, 0x00, 0x01, 0x0D, 0x01, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x11, 0x05, 0x00, 0x00, 0x01, 0x01, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x11, 0x08,
+        0x00, 0x00, 0x01, 0x05, 0x03, 0x00, 0x20, 0x4E, 0x00, 0x00, 0x11, 0x05, 0x00, 0x00, 0x01,
+        0x08, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x11, 0x05, 0x00, 0x00, 0x04, 0x04, 0x01, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x11, 0x0C, 0x00, 0x00, 0x08, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x0C, 0x00, 0x00, 0x08, 0x01, 0x80, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x0C, 0x00, 0x00, 0x08, 0x02, 0x80, 0x00, 0x00,
+        0x08, 0x00, 0x08, 0x00, 0x08, 0x00, 0x08, 0x11, 0x0C, 0x00, 0x00, 0x08, 0x03, 0x80, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    pub(crate) const SHORT_REAL_PACKET_ONE: [u8; 23] = [
+        0x02, 0x06, 0x00, 0x00, 0x00, 0x02, 0x80, 0x00, 0x64, 0x20, 0x00, 0x00, 0x02, 0x06, 0x00,
+        0x00, 0x00, 0x02, 0x80, 0x00, 0x07, 0x21, 0x00,
+    ];
 }
 
 #[cfg(test)]
@@ -237,6 +603,7 @@ mod packet_data_test {
     };
 
     use super::*;
+    use crate::test_packets::*;
 
     #[test]
     fn new() {
@@ -246,7 +613,7 @@ mod packet_data_test {
             0x00, 0x00, 0x00, // Padding
         ];
 
-        if let Ok(packet) = PacketData::new(packet_data) {
+        if let Ok(packet) = PacketData::new(&packet_data) {
             assert_eq!(packet.cursor, 0);
             assert_eq!(packet.data.len(), 12);
         } else {
@@ -263,7 +630,7 @@ mod packet_data_test {
         ];
 
         let mut packet =
-            PacketData::new(packet_data).expect("Failed to build PacketData with known good data");
+            PacketData::new(&packet_data).expect("Failed to build PacketData with known good data");
 
         if let Ok(header) = packet.parse_header() {
             assert_eq!(
@@ -287,7 +654,7 @@ mod packet_data_test {
             0x00, 0x00, 0x00, // Padding
         ];
 
-        let mut packet = PacketData::new(packet_data).expect("Verified packet data");
+        let mut packet = PacketData::new(&packet_data).expect("Verified packet data");
 
         if let Err(error) = packet.parse_header() {
             assert_eq!(error, EldritchError::InvalidHeader);
@@ -305,7 +672,7 @@ mod packet_data_test {
             0x00, 0x00, 0x00, // Padding
         ];
 
-        let mut packet = PacketData::new(packet_data).expect("Verified packet data");
+        let mut packet = PacketData::new(&packet_data).expect("Verified packet data");
         packet.cursor = 10;
         println!("Packet: {:?}", packet);
         assert!(packet.has_data());
@@ -319,7 +686,7 @@ mod packet_data_test {
             0x00, 0x00, 0x00, // Padding
         ];
 
-        let mut packet = PacketData::new(packet_data).expect("Verified packet data");
+        let mut packet = PacketData::new(&packet_data).expect("Verified packet data");
         packet.cursor = 12;
         println!("Packet: {:?}", packet);
         assert!(!packet.has_data());
@@ -332,7 +699,7 @@ mod packet_data_test {
             0x00, 0x80, 0x01, 0x9a, 0xfd, // Command
             0x00, 0x00, 0x00, // Padding
         ];
-        let mut packet = PacketData::new(packet_data).expect("Verified packet data");
+        let mut packet = PacketData::new(&packet_data).expect("Verified packet data");
         packet.cursor = 4;
 
         if let Ok(cmd_data) = packet.get_slice(5) {
@@ -356,7 +723,7 @@ mod packet_data_test {
             0x00, 0x80, 0x01, 0x9a, 0xfd, // Command
             0x00, 0x00, 0x00, // Padding
         ];
-        let mut packet = PacketData::new(packet_data).expect("Verified packet data");
+        let mut packet = PacketData::new(&packet_data).expect("Verified packet data");
         packet.cursor = 8;
 
         if let Err(error) = packet.get_slice(5) {
@@ -368,15 +735,7 @@ mod packet_data_test {
 
     #[test]
     fn parse_large_packet() {
-        let frame_packet = vec![
-            0x04, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0xff, 0x05, 0x00, 0x00, 0x00, 0x06,
-            0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x04, 0x08, 0x00, 0x00, 0x01, 0x05, 0x03, 0x00,
-            0x10, 0x27, 0x00, 0x00, 0x04, 0x06, 0x00, 0x00, 0x04, 0x02, 0x80, 0x01, 0x33, 0x01,
-            0x00, 0x00, 0xff, 0x09, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x18, 0x01, 0x03, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x04, 0x0c, 0x00, 0x00, 0x08, 0x01, 0x80, 0x01, 0x00, 0x00,
-            0x9a, 0xfd, 0x9a, 0xfd, 0x00, 0x00,
-        ];
-        let commands = parse_frame_packet(frame_packet).expect("Test Frame should parse");
+        let commands = parse_frame_packet(LARGE_PACKET.to_vec()).expect("Test Frame should parse");
         let mut commands = commands.iter();
 
         assert_eq!(
@@ -468,97 +827,99 @@ mod packet_data_test {
 
     #[test]
     fn long_real_packet_one() {
-        let cmd_data: [u8; 243] = [
-            0x01, 0x06, 0x00, 0x00, 0x00, 0x02, 0x80, 0x00, 0x8D, 0x1C, 0x00, 0x00, 0x01, 0x06,
-            0x00, 0x00, 0x01, 0x10, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x05, 0x00, 0x00,
-            0x01, 0x0D, 0x01, 0x00, 0x04, 0x00, 0x00, 0x00, 0x01, 0x05, 0x00, 0x00, 0x01, 0x01,
-            0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x08, 0x00, 0x00, 0x01, 0x02, 0x02, 0x00,
-            0x04, 0x10, 0x00, 0x00, 0x01, 0x08, 0x00, 0x00, 0x01, 0x05, 0x03, 0x00, 0x1B, 0x41,
-            0x00, 0x00, 0x01, 0x05, 0x00, 0x00, 0x01, 0x08, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00,
-            0x01, 0x05, 0x00, 0x00, 0x04, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x0C,
-            0x00, 0x00, 0x08, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x45, 0x00,
-            0x01, 0x0C, 0x00, 0x00, 0x08, 0x01, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x01, 0x0C, 0x00, 0x00, 0x08, 0x02, 0x80, 0x00, 0x00, 0x08, 0x00, 0x08,
-            0x00, 0x08, 0xCE, 0x07, 0x01, 0x0C, 0x00, 0x00, 0x08, 0x03, 0x80, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x08, 0x00, 0x00, 0x08, 0x04, 0x80, 0x00,
-            0x00, 0x04, 0x00, 0x08, 0x01, 0x06, 0x00, 0x00, 0x08, 0x05, 0x80, 0x00, 0x00, 0x08,
-            0x00, 0x00, 0x01, 0x08, 0x00, 0x00, 0x08, 0x06, 0x80, 0x00, 0x00, 0x00, 0x00, 0x08,
-            0x02, 0x06, 0x00, 0x00, 0x00, 0x02, 0x80, 0x00, 0x9B, 0x12, 0x00, 0x00, 0x02, 0x06,
-            0x00, 0x00, 0x01, 0x10, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x05, 0x00, 0x00,
-            0x01, 0x0D, 0x01, 0x00, 0x08, 0x00, 0x00, 0x00, 0x02, 0x05, 0x00, 0x00, 0x01, 0x01,
-            0x01, 0x00, 0x08, 0x00, 0x00,
-        ];
-        let result = parse_frame_packet(cmd_data.to_vec());
+        let result = parse_frame_packet(LONG_REAL_PACKET_ONE.to_vec());
         let _commands = result.unwrap();
     }
 
     #[test]
     fn long_real_packet_two() {
-        let cmd_data: [u8; 251] = [
-            0x03, 0x0C, 0x00, 0x00, 0x08, 0x02, 0x80, 0x00, 0x00, 0x08, 0x00, 0x08, 0x00, 0x08,
-            0x00, 0x08, 0x03, 0x0C, 0x00, 0x00, 0x08, 0x03, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x03, 0x08, 0x00, 0x00, 0x08, 0x04, 0x80, 0x00, 0x00, 0x04,
-            0x00, 0x08, 0x03, 0x06, 0x00, 0x00, 0x08, 0x05, 0x80, 0x00, 0x00, 0x08, 0x00, 0x00,
-            0x03, 0x08, 0x00, 0x00, 0x08, 0x06, 0x80, 0x00, 0x00, 0x00, 0x00, 0x08, 0x04, 0x06,
-            0x00, 0x00, 0x00, 0x02, 0x80, 0x00, 0xF2, 0x15, 0x00, 0x00, 0x04, 0x06, 0x00, 0x00,
-            0x01, 0x10, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x05, 0x00, 0x00, 0x01, 0x0D,
-            0x01, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x04, 0x05, 0x00, 0x00, 0x01, 0x01, 0x01, 0x00,
-            0x02, 0x00, 0x00, 0x00, 0x04, 0x08, 0x00, 0x00, 0x01, 0x02, 0x02, 0x00, 0x04, 0x10,
-            0x00, 0x00, 0x04, 0x08, 0x00, 0x00, 0x01, 0x05, 0x03, 0x00, 0x1B, 0x41, 0x00, 0x00,
-            0x04, 0x05, 0x00, 0x00, 0x01, 0x08, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x04, 0x05,
-            0x00, 0x00, 0x04, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x0C, 0x00, 0x00,
-            0x08, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x0C,
-            0x00, 0x00, 0x08, 0x01, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x04, 0x0C, 0x00, 0x00, 0x08, 0x02, 0x80, 0x00, 0x00, 0x08, 0x00, 0x08, 0x00, 0x08,
-            0x00, 0x08, 0x04, 0x0C, 0x00, 0x00, 0x08, 0x03, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x04, 0x08, 0x00, 0x00, 0x08, 0x04, 0x80, 0x00, 0x00, 0x04,
-            0x00, 0x08, 0x04, 0x06, 0x00, 0x00, 0x08, 0x05, 0x80, 0x00, 0x00, 0x08, 0x00,
-        ];
-        let result = parse_frame_packet(cmd_data.to_vec());
+        let result = parse_frame_packet(LONG_REAL_PACKET_TWO.to_vec());
         let _commands = result.unwrap();
     }
 
     #[test]
     fn long_real_packet_three() {
-        let cmd_data: [u8; 247] = [
-            0x10, 0x05, 0x00, 0x00, 0x04, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x0C,
-            0x00, 0x00, 0x08, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x10, 0x0C, 0x00, 0x00, 0x08, 0x01, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x10, 0x0C, 0x00, 0x00, 0x08, 0x02, 0x80, 0x00, 0x00, 0x08, 0x00, 0x08,
-            0x00, 0x08, 0x00, 0x08, 0x10, 0x0C, 0x00, 0x00, 0x08, 0x03, 0x80, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x08, 0x00, 0x00, 0x08, 0x04, 0x80, 0x00,
-            0x00, 0x04, 0x00, 0x08, 0x10, 0x06, 0x00, 0x00, 0x08, 0x05, 0x80, 0x00, 0x00, 0x08,
-            0x00, 0x00, 0x10, 0x08, 0x00, 0x00, 0x08, 0x06, 0x80, 0x00, 0x00, 0x00, 0x00, 0x08,
-            0x11, 0x06, 0x00, 0x00, 0x01, 0x10, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x05,
-            0x00, 0x00, 0x01, 0x0D, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x05, 0x00, 0x00,
-            0x01, 0x01, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x11, 0x08, 0x00, 0x00, 0x01, 0x05,
-            0x03, 0x00, 0x20, 0x4E, 0x00, 0x00, 0x11, 0x05, 0x00, 0x00, 0x01, 0x08, 0x01, 0x00,
-            0x01, 0x00, 0x00, 0x00, 0x11, 0x05, 0x00, 0x00, 0x04, 0x04, 0x01, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x11, 0x0C, 0x00, 0x00, 0x08, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x11, 0x0C, 0x00, 0x00, 0x08, 0x01, 0x80, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x0C, 0x00, 0x00, 0x08, 0x02, 0x80, 0x00,
-            0x00, 0x08, 0x00, 0x08, 0x00, 0x08, 0x00, 0x08, 0x11, 0x0C, 0x00, 0x00, 0x08, 0x03,
-            0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        ];
-        let result = parse_frame_packet(cmd_data.to_vec());
+        let result = parse_frame_packet(LONG_REAL_PACKET_THREE.to_vec());
         let _commands = result.unwrap();
     }
 
     #[test]
     fn short_real_packet_one() {
-        let cmd_data: [u8; 23] = [
-            0x02, 0x06, 0x00, 0x00, 0x00, 0x02, 0x80, 0x00, 0x64, 0x20, 0x00, 0x00, 0x02, 0x06,
-            0x00, 0x00, 0x00, 0x02, 0x80, 0x00, 0x07, 0x21, 0x00,
-        ];
-        let result = parse_frame_packet(cmd_data.to_vec());
+        let result = parse_frame_packet(SHORT_REAL_PACKET_ONE.to_vec());
         let _commands = result.unwrap();
     }
 }
 
+#[cfg(test)]
+mod encode_packet_test {
+    use super::*;
+    use crate::test_packets::*;
+
+    #[test]
+    fn encode_large_packet_round_trip() {
+        let commands = parse_frame_packet(LARGE_PACKET.to_vec()).expect("Test Frame should parse");
+        let packet = encode_frame_packet(&commands).expect("Parsed commands should encode");
+        assert_eq!(LARGE_PACKET.to_vec(), packet);
+    }
+
+    // In most of the real packets the missing byte is padding, so encoding adds it back.
+    #[test]
+    fn encode_long_real_packet_one_round_trip() {
+        let commands = parse_frame_packet(LONG_REAL_PACKET_ONE.to_vec()).unwrap();
+        let packet = encode_frame_packet(&commands).unwrap();
+        assert_eq!(LONG_REAL_PACKET_ONE, packet[..LONG_REAL_PACKET_ONE.len()]);
+        assert_eq!(&[0x00], &packet[LONG_REAL_PACKET_ONE.len()..]);
+    }
+
+    #[test]
+    fn encode_long_real_packet_two_round_trip() {
+        let commands = parse_frame_packet(LONG_REAL_PACKET_TWO.to_vec()).unwrap();
+        let packet = encode_frame_packet(&commands).unwrap();
+        assert_eq!(LONG_REAL_PACKET_TWO, packet[..LONG_REAL_PACKET_TWO.len()]);
+        assert_eq!(&[0x00], &packet[LONG_REAL_PACKET_TWO.len()..]);
+    }
+
+    #[test]
+    fn encode_long_real_packet_three_round_trip() {
+        // Here the missing byte cuts into the last command: its header asks for 12 bytes of
+        // command data and only 11 are left. Nothing past offset 232 can be parsed, so only the
+        // commands before it can round trip.
+        let commands = parse_frame_packet(LONG_REAL_PACKET_THREE.to_vec()).unwrap();
+        let packet = encode_frame_packet(&commands).unwrap();
+        assert_eq!(LONG_REAL_PACKET_THREE[..232], packet);
+
+        let frame = parse_frame_packet_lenient(LONG_REAL_PACKET_THREE.to_vec()).unwrap();
+        assert_eq!(frame.commands, commands);
+        assert_eq!(
+            frame.errors,
+            vec![CommandParseError {
+                context: ErrorContext {
+                    offset: 232,
+                    index: 233,
+                    device_id: 0x11,
+                    category: Some(0x08),
+                    parameter: Some(0x03),
+                },
+                error: EldritchError::EndOfPacket,
+            }]
+        );
+    }
+
+    #[test]
+    fn encode_short_real_packet_one_round_trip() {
+        let commands = parse_frame_packet(SHORT_REAL_PACKET_ONE.to_vec()).unwrap();
+        let packet = encode_frame_packet(&commands).unwrap();
+        assert_eq!(SHORT_REAL_PACKET_ONE, packet[..SHORT_REAL_PACKET_ONE.len()]);
+        assert_eq!(&[0x00], &packet[SHORT_REAL_PACKET_ONE.len()..]);
+    }
+}
+
 #[cfg(test)]
 mod lib_test {
     use super::*;
-    use crate::commands::{lens_commands::LensCommand, Command};
+    use crate::commands::{
+        lens_commands::LensCommand, metadata_commands::MetadataCommand, Command,
+    };
 
     #[test]
     fn parse_packet_single_command() {
@@ -588,6 +949,13 @@ mod lib_test {
         }
     }
 
+    #[test]
+    fn toggle_round_trips_through_a_frame_packet() {
+        let command: AddressedCommand = "cam 1 audio.phantom_power toggle".parse().unwrap();
+        let packet = encode_frame_packet(core::slice::from_ref(&command)).unwrap();
+        assert_eq!(parse_frame_packet(packet), Ok(vec![command]));
+    }
+
     #[test]
     fn parse_packet_two_commands() {
         let packet_data = vec![
@@ -633,6 +1001,287 @@ mod lib_test {
         }
     }
 
+    #[test]
+    fn encode_packet_single_command() {
+        let commands = [AddressedCommand {
+            device_id: 0,
+            command: Command::Lens(LensCommand::Focus {
+                operation: Operation::Increment,
+                data: FixedPointDecimal {
+                    raw_val: 0x0133u16 as i16,
+                },
+            }),
+        }];
+
+        assert_eq!(
+            Ok(vec![
+                0x00, 0x06, 0x00, 0x00, // Header
+                0x00, 0x00, 0x80, 0x01, 0x33, 0x01, // Command
+                0x00, 0x00, // Padding
+            ]),
+            encode_frame_packet(&commands)
+        );
+    }
+
+    #[test]
+    fn encode_packet_too_large() {
+        let command = AddressedCommand {
+            device_id: 0,
+            command: Command::Lens(LensCommand::InstantaneousAutoFocus),
+        };
+        // Each void command takes up 8 bytes, 32 of them is one byte to many.
+        let commands = vec![command; 32];
+
+        assert_eq!(
+            Err(EldritchError::PacketToLarge),
+            encode_frame_packet(&commands)
+        );
+        assert_eq!(
+            Ok(248),
+            encode_frame_packet(&commands[..31]).map(|p| p.len())
+        );
+    }
+
+    #[cfg(feature = "bounds-checked")]
+    #[test]
+    fn encode_packet_string_too_long() {
+        let commands = [AddressedCommand {
+            device_id: 1,
+            command: Command::Metadata(MetadataCommand::ProjectName {
+                operation: Operation::Assign,
+                data: "A".repeat(40),
+            }),
+        }];
+
+        assert_eq!(
+            Err(EldritchError::DataOutOfBounds),
+            encode_frame_packet(&commands)
+        );
+    }
+
+    #[test]
+    fn encode_packets_splits_at_size_limit() {
+        let command = AddressedCommand {
+            device_id: 0,
+            command: Command::Lens(LensCommand::InstantaneousAutoFocus),
+        };
+        let commands = vec![command; 63];
+        let packets = encode_frame_packets(&commands).expect("Every command fits in a packet");
+
+        assert_eq!(
+            packets.iter().map(|p| p.len()).collect::<Vec<_>>(),
+            vec![248, 248, 8]
+        );
+        assert_eq!(
+            packets
+                .iter()
+                .map(|p| parse_frame_packet(p.clone()))
+                .collect::<Result<Vec<_>, _>>()
+                .map(|commands| commands.concat()),
+            Ok(commands)
+        );
+    }
+
+    #[test]
+    fn encode_packets_empty() {
+        assert_eq!(encode_frame_packets(&[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn parse_packet_unknown_category() {
+        let packet_data = vec![
+            0x00, 0x05, 0x00, 0x00, // Header
+            0x7f, 0x00, 0x00, 0x00, 0x01, // Command
+            0x00, 0x00, 0x00, // Padding
+        ];
+
+        assert_eq!(
+            Err(EldritchError::InFrame {
+                context: ErrorContext {
+                    offset: 0,
+                    index: 4,
+                    device_id: 0,
+                    category: Some(0x7f),
+                    parameter: Some(0x00),
+                },
+                error: Box::new(EldritchError::UnknownCategory {
+                    category: 0x7f,
+                    raw: vec![0x7f, 0x00, 0x00, 0x00, 0x01]
+                }),
+            }),
+            parse_frame_packet(packet_data)
+        );
+    }
+
+    #[test]
+    fn parse_packet_command_length_past_end_of_full_packet() {
+        let mut packet_data = [0x00, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00].repeat(31);
+        packet_data.extend_from_slice(&[0x00, 0xff, 0x00, 0x00, 0x00, 0x01, 0x00]);
+
+        assert_eq!(
+            Ok(31),
+            parse_frame_packet(packet_data).map(|cmds| cmds.len())
+        );
+    }
+
+    #[test]
+    fn parse_packet_lenient_skips_bad_command() {
+        let packet_data = vec![
+            0x04, 0x06, 0x00, 0x00, // Header
+            0x00, 0x04, 0x01, 0x00, 0x10, 0x27, // Bad data type
+            0x00, 0x00, // Padding
+            0x05, 0x05, 0x00, 0x00, // Header
+            0x7f, 0x00, 0x00, 0x00, 0x01, // Unknown category
+            0x00, 0x00, 0x00, // Padding
+            0x06, 0x06, 0x00, 0x00, // Header
+            0x00, 0x00, 0x80, 0x01, 0x33, 0x01, // Command
+            0x00, 0x00, // Padding
+        ];
+
+        let frame = parse_frame_packet_lenient(packet_data).expect("Packet is not to large");
+        assert_eq!(
+            frame.commands,
+            vec![AddressedCommand {
+                device_id: 6,
+                command: Command::Lens(LensCommand::Focus {
+                    operation: Operation::Increment,
+                    data: FixedPointDecimal {
+                        raw_val: 0x0133u16 as i16
+                    }
+                })
+            }]
+        );
+        assert_eq!(
+            frame.errors,
+            vec![
+                CommandParseError {
+                    context: ErrorContext {
+                        offset: 0,
+                        index: 6,
+                        device_id: 4,
+                        category: Some(0x00),
+                        parameter: Some(0x04),
+                    },
+                    error: EldritchError::InvalidDataType {
+                        data_type: 0x01,
+                        expected: 0x02,
+                        raw: vec![0x00, 0x04, 0x01, 0x00, 0x10, 0x27],
+                    }
+                },
+                CommandParseError {
+                    context: ErrorContext {
+                        offset: 12,
+                        index: 16,
+                        device_id: 5,
+                        category: Some(0x7f),
+                        parameter: Some(0x00),
+                    },
+                    error: EldritchError::UnknownCategory {
+                        category: 0x7f,
+                        raw: vec![0x7f, 0x00, 0x00, 0x00, 0x01],
+                    }
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_packet_lenient_keeps_command_with_bad_padding() {
+        let packet_data = vec![
+            0x00, 0x06, 0x00, 0x00, // Header
+            0x00, 0x00, 0x80, 0x01, 0x33, 0x01, // Command
+            0x00, 0xff, // Bad padding
+            0x01, 0x04, 0x00, 0x00, // Header
+            0x00, 0x01, 0x00, 0x00, // Command
+        ];
+
+        let frame = parse_frame_packet_lenient(packet_data).expect("Packet is not to large");
+        assert_eq!(frame.commands.len(), 2);
+        assert_eq!(
+            frame.errors,
+            vec![CommandParseError {
+                context: ErrorContext {
+                    offset: 0,
+                    index: 11,
+                    device_id: 0,
+                    category: Some(0x00),
+                    parameter: Some(0x00),
+                },
+                error: EldritchError::NonZeroPadding { index: 1 },
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_packet_lenient_stops_at_bad_header() {
+        let packet_data = vec![
+            0x00, 0x04, 0x00, 0x00, // Header
+            0x00, 0x01, 0x00, 0x00, // Command
+            0x03, 0x04, 0x00, 0xff, // Bad header
+            0x00, 0x01, 0x00, 0x00, // Command
+        ];
+
+        let frame = parse_frame_packet_lenient(packet_data).expect("Packet is not to large");
+        assert_eq!(frame.commands.len(), 1);
+        assert_eq!(
+            frame.errors,
+            vec![CommandParseError {
+                context: ErrorContext {
+                    offset: 8,
+                    index: 11,
+                    device_id: 3,
+                    category: Some(0x00),
+                    parameter: Some(0x01),
+                },
+                error: EldritchError::InvalidHeader,
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_packet_lenient_reports_truncated_command() {
+        let packet_data = vec![
+            0x00, 0x06, 0x00, 0x00, // Header
+            0x00, 0x00, 0x80, 0x01, 0x33, 0x01, // Command
+            0x00, 0x00, // Padding
+            0x02, 0x06, 0x00, 0x00, // Header
+            0x00, 0x00, 0x80, // Command cut off after 3 of 6 bytes
+        ];
+
+        let frame =
+            parse_frame_packet_lenient(packet_data.clone()).expect("Packet is not to large");
+        assert_eq!(frame.commands.len(), 1);
+        assert_eq!(
+            frame.errors,
+            vec![CommandParseError {
+                context: ErrorContext {
+                    offset: 12,
+                    index: 13,
+                    device_id: 2,
+                    category: Some(0x00),
+                    parameter: Some(0x00),
+                },
+                error: EldritchError::EndOfPacket,
+            }]
+        );
+
+        // The strict parser drops the truncated command.
+        assert_eq!(parse_frame_packet(packet_data), Ok(frame.commands));
+    }
+
+    #[test]
+    fn parse_packet_lenient_matches_strict_on_good_packet() {
+        let packet_data = vec![
+            0x00, 0x06, 0x00, 0x00, // Header
+            0x00, 0x00, 0x80, 0x01, 0x33, 0x01, // Command
+            0x00, 0x00, // Padding
+        ];
+
+        let frame = parse_frame_packet_lenient(packet_data.clone()).expect("Known good packet");
+        assert!(frame.errors.is_empty());
+        assert_eq!(Ok(frame.commands), parse_frame_packet(packet_data));
+    }
+
     #[test]
     fn calculate_padding_length_no_padding() {
         assert_eq!(0_u8, calculate_padding_length(8));
@@ -671,9 +1320,9 @@ mod lib_test {
         let command_length = 5;
 
         assert_eq!(
-            Err(EldritchError::PaddingViolation(String::from(
+            Err(EldritchError::PaddingViolation(
                 "Padding length is incorrect"
-            ))),
+            )),
             verify_padding(&padding, command_length)
         );
     }
@@ -684,9 +1333,7 @@ mod lib_test {
         let command_length = 5;
 
         assert_eq!(
-            Err(EldritchError::PaddingViolation(String::from(
-                "Padding byte at index: 0 is not 0x00"
-            ))),
+            Err(EldritchError::NonZeroPadding { index: 0 }),
             verify_padding(&padding, command_length),
         )
     }
@@ -697,9 +1344,7 @@ mod lib_test {
         let command_length = 5;
 
         assert_eq!(
-            Err(EldritchError::PaddingViolation(String::from(
-                "Padding byte at index: 2 is not 0x00"
-            ))),
+            Err(EldritchError::NonZeroPadding { index: 2 }),
             verify_padding(&padding, command_length),
         )
     }
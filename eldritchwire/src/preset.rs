@@ -0,0 +1,271 @@
+use crate::{commands::Command, AddressedCommand, CameraState, BROADCAST_DEVICE_ID};
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt};
+
+/// Version written to new preset files. Files with a newer version are rejected when loading.
+pub const PRESET_VERSION: u32 = 1;
+
+/// A saved camera setup, stored as the `Assign` command for every parameter it covers.
+///
+/// Presets are not tied to a camera, [`Preset::to_commands`] addresses them to any device id.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Preset {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub commands: Vec<Command>,
+}
+
+#[derive(Debug)]
+pub enum PresetError {
+    UnsupportedVersion { version: u32 },
+    TomlDeserialize(toml::de::Error),
+    TomlSerialize(toml::ser::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetError::UnsupportedVersion { version } => write!(
+                f,
+                "Preset version {version} is newer than the supported version {PRESET_VERSION}"
+            ),
+            PresetError::TomlDeserialize(err) => write!(f, "Invalid preset TOML: {err}"),
+            PresetError::TomlSerialize(err) => write!(f, "Unable to write preset TOML: {err}"),
+            PresetError::Json(err) => write!(f, "Invalid preset JSON: {err}"),
+        }
+    }
+}
+
+impl Error for PresetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PresetError::UnsupportedVersion { .. } => None,
+            PresetError::TomlDeserialize(err) => Some(err),
+            PresetError::TomlSerialize(err) => Some(err),
+            PresetError::Json(err) => Some(err),
+        }
+    }
+}
+
+/// Only the version is read first, so a file from a newer crate is reported as such instead of
+/// failing on whatever changed in its layout.
+#[derive(Deserialize)]
+struct PresetVersion {
+    version: u32,
+}
+
+impl Preset {
+    pub fn from_state(state: &CameraState) -> Self {
+        Self {
+            version: PRESET_VERSION,
+            name: None,
+            commands: state.values(),
+        }
+    }
+
+    /// Captures the values the commands for `device_id` leave the camera in, broadcast commands
+    /// are included.
+    pub fn from_commands<'a>(
+        commands: impl IntoIterator<Item = &'a AddressedCommand>,
+        device_id: u8,
+    ) -> Self {
+        Self::from_state(
+            &commands
+                .into_iter()
+                .filter(|command| {
+                    command.device_id == device_id || command.device_id == BROADCAST_DEVICE_ID
+                })
+                .map(|command| &command.command)
+                .collect(),
+        )
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn to_state(&self) -> CameraState {
+        self.commands.iter().collect()
+    }
+
+    pub fn to_commands(&self, device_id: u8) -> Vec<AddressedCommand> {
+        self.commands
+            .iter()
+            .map(|command| AddressedCommand {
+                device_id,
+                command: command.clone(),
+            })
+            .collect()
+    }
+
+    pub fn from_toml(preset: &str) -> Result<Self, PresetError> {
+        check_version(
+            toml::from_str::<PresetVersion>(preset).map_err(PresetError::TomlDeserialize)?,
+        )?;
+        toml::from_str(preset).map_err(PresetError::TomlDeserialize)
+    }
+
+    pub fn to_toml(&self) -> Result<String, PresetError> {
+        toml::to_string_pretty(self).map_err(PresetError::TomlSerialize)
+    }
+
+    pub fn from_json(preset: &str) -> Result<Self, PresetError> {
+        check_version(serde_json::from_str::<PresetVersion>(preset).map_err(PresetError::Json)?)?;
+        serde_json::from_str(preset).map_err(PresetError::Json)
+    }
+
+    pub fn to_json(&self) -> Result<String, PresetError> {
+        serde_json::to_string_pretty(self).map_err(PresetError::Json)
+    }
+}
+
+fn check_version(preset: PresetVersion) -> Result<(), PresetError> {
+    if preset.version > PRESET_VERSION {
+        return Err(PresetError::UnsupportedVersion {
+            version: preset.version,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod preset_test {
+    use super::*;
+    use crate::{
+        commands::{
+            color_correction_commands::{ColorCorrectionCommand, RedGreenBlueLuma},
+            lens_commands::LensCommand,
+            video_commands::{ManualWhiteBalanceData, VideoCommand},
+        },
+        FixedPointDecimal, Operation,
+    };
+
+    fn fpd(real_val: f32) -> FixedPointDecimal {
+        FixedPointDecimal::from_real_val(real_val).expect("Value is in range")
+    }
+
+    fn shading() -> Vec<AddressedCommand> {
+        vec![
+            AddressedCommand {
+                device_id: 1,
+                command: Command::ColorCorrection(ColorCorrectionCommand::GainAdjust {
+                    operation: Operation::Assign,
+                    data: RedGreenBlueLuma {
+                        red: fpd(1.25),
+                        green: fpd(1.0),
+                        blue: fpd(0.875),
+                        luma: fpd(1.0),
+                    },
+                }),
+            },
+            AddressedCommand {
+                device_id: 1,
+                command: Command::Video(VideoCommand::ManualWhiteBalance {
+                    operation: Operation::Assign,
+                    data: ManualWhiteBalanceData {
+                        color_temp: 5600,
+                        tint: -3,
+                    },
+                }),
+            },
+            AddressedCommand {
+                device_id: 2,
+                command: Command::Video(VideoCommand::ISO {
+                    operation: Operation::Assign,
+                    data: 1600,
+                }),
+            },
+            AddressedCommand {
+                device_id: BROADCAST_DEVICE_ID,
+                command: Command::Lens(LensCommand::ApatureFStop {
+                    operation: Operation::Assign,
+                    data: fpd(4.0),
+                }),
+            },
+        ]
+    }
+
+    #[test]
+    fn from_commands_only_keeps_device() {
+        let preset = Preset::from_commands(&shading(), 1);
+
+        assert_eq!(preset.version, PRESET_VERSION);
+        assert_eq!(preset.commands.len(), 3);
+        assert_eq!(
+            preset.to_commands(4),
+            [&shading()[3], &shading()[1], &shading()[0]]
+                .into_iter()
+                .map(|command| AddressedCommand {
+                    device_id: 4,
+                    command: command.command.clone()
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn toml_round_trip() {
+        let preset = Preset::from_commands(&shading(), 1).with_name("Studio A");
+        let toml = preset.to_toml().expect("Preset serializes");
+
+        assert!(toml.contains("name = \"Studio A\""));
+        assert_eq!(Preset::from_toml(&toml).expect("Preset parses"), preset);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let preset = Preset::from_commands(&shading(), 1);
+        let json = preset.to_json().expect("Preset serializes");
+
+        assert_eq!(Preset::from_json(&json).expect("Preset parses"), preset);
+    }
+
+    #[test]
+    fn hand_written_toml() {
+        let preset = Preset::from_toml(
+            r#"
+            version = 1
+
+            [[commands]]
+            Lens.Focus = { operation = "Assign", data = 0.5 }
+
+            [[commands]]
+            Video.ISO = { operation = "Assign", data = 800 }
+            "#,
+        )
+        .expect("Preset parses");
+
+        let state = preset.to_state();
+        assert_eq!(state.lens.focus, Some(fpd(0.5)));
+        assert_eq!(state.video.iso, Some(800));
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let preset = Preset::from_toml(
+            r#"
+            version = 2
+            commands = "a layout from the future"
+            "#,
+        );
+        assert!(matches!(
+            preset,
+            Err(PresetError::UnsupportedVersion { version: 2 })
+        ));
+    }
+
+    #[test]
+    fn out_of_range_fixed_point_is_rejected() {
+        let preset = Preset::from_json(
+            r#"{
+                "version": 1,
+                "commands": [{ "Lens": { "Focus": { "operation": "Assign", "data": 16.0 } } }]
+            }"#,
+        );
+        assert!(matches!(preset, Err(PresetError::Json(_))));
+    }
+}
@@ -0,0 +1,478 @@
+use crate::{
+    commands::{
+        audio_commands::AudioState, color_correction_commands::ColorCorrectionState,
+        configuration_commands::ConfigurationState, display_commands::DisplayState,
+        lens_commands::LensState, media_commands::MediaState, metadata_commands::MetadataState,
+        output_commands::OutputState, ptz_control_commands::PtzControlState,
+        reference_commands::ReferenceState, tally_commands::TallyState, video_commands::VideoState,
+        Command,
+    },
+    AddressedCommand,
+};
+use alloc::{collections::BTreeMap, vec::Vec};
+
+/// Commands sent to this device id apply to every camera.
+pub const BROADCAST_DEVICE_ID: u8 = 255;
+
+/// Last known value of every parameter of a single camera, `None` until it has been assigned.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CameraState {
+    pub lens: LensState,
+    pub video: VideoState,
+    pub audio: AudioState,
+    pub output: OutputState,
+    pub display: DisplayState,
+    pub tally: TallyState,
+    pub reference: ReferenceState,
+    pub configuration: ConfigurationState,
+    pub color_correction: ColorCorrectionState,
+    pub media: MediaState,
+    pub ptz_control: PtzControlState,
+    pub metadata: MetadataState,
+}
+
+impl CameraState {
+    pub fn apply(&mut self, command: &Command) {
+        match command {
+            Command::Lens(cmd) => self.lens.apply(cmd),
+            Command::Video(cmd) => self.video.apply(cmd),
+            Command::Audio(cmd) => self.audio.apply(cmd),
+            Command::Output(cmd) => self.output.apply(cmd),
+            Command::Display(cmd) => self.display.apply(cmd),
+            Command::Tally(cmd) => self.tally.apply(cmd),
+            Command::Reference(cmd) => self.reference.apply(cmd),
+            Command::Configuration(cmd) => self.configuration.apply(cmd),
+            Command::ColorCorrection(cmd) => self.color_correction.apply(cmd),
+            Command::Media(cmd) => self.media.apply(cmd),
+            Command::PtzControl(cmd) => self.ptz_control.apply(cmd),
+            Command::Metadata(cmd) => self.metadata.apply(cmd),
+            Command::Unknown { .. } => {}
+        }
+    }
+
+    /// The known value of a parameter as an `Assign` command.
+    pub fn value(&self, category: u8, parameter: u8) -> Option<Command> {
+        match category {
+            0x00 => self.lens.value(parameter).map(Command::Lens),
+            0x01 => self.video.value(parameter).map(Command::Video),
+            0x02 => self.audio.value(parameter).map(Command::Audio),
+            0x03 => self.output.value(parameter).map(Command::Output),
+            0x04 => self.display.value(parameter).map(Command::Display),
+            0x05 => self.tally.value(parameter).map(Command::Tally),
+            0x06 => self.reference.value(parameter).map(Command::Reference),
+            0x07 => self
+                .configuration
+                .value(parameter)
+                .map(Command::Configuration),
+            0x08 => self
+                .color_correction
+                .value(parameter)
+                .map(Command::ColorCorrection),
+            0x0a => self.media.value(parameter).map(Command::Media),
+            0x0b => self.ptz_control.value(parameter).map(Command::PtzControl),
+            0x0c => self.metadata.value(parameter).map(Command::Metadata),
+            _ => None,
+        }
+    }
+
+    /// Every known value as an `Assign` command, in category and parameter order.
+    pub fn values(&self) -> Vec<Command> {
+        let mut values = Vec::new();
+        values.extend(self.lens.values().into_iter().map(Command::Lens));
+        values.extend(self.video.values().into_iter().map(Command::Video));
+        values.extend(self.audio.values().into_iter().map(Command::Audio));
+        values.extend(self.output.values().into_iter().map(Command::Output));
+        values.extend(self.display.values().into_iter().map(Command::Display));
+        values.extend(self.tally.values().into_iter().map(Command::Tally));
+        values.extend(self.reference.values().into_iter().map(Command::Reference));
+        values.extend(
+            self.configuration
+                .values()
+                .into_iter()
+                .map(Command::Configuration),
+        );
+        values.extend(
+            self.color_correction
+                .values()
+                .into_iter()
+                .map(Command::ColorCorrection),
+        );
+        values.extend(self.media.values().into_iter().map(Command::Media));
+        values.extend(
+            self.ptz_control
+                .values()
+                .into_iter()
+                .map(Command::PtzControl),
+        );
+        values.extend(self.metadata.values().into_iter().map(Command::Metadata));
+        values
+    }
+}
+
+impl<'a> Extend<&'a Command> for CameraState {
+    fn extend<T: IntoIterator<Item = &'a Command>>(&mut self, commands: T) {
+        commands.into_iter().for_each(|command| self.apply(command));
+    }
+}
+
+impl<'a> FromIterator<&'a Command> for CameraState {
+    fn from_iter<T: IntoIterator<Item = &'a Command>>(commands: T) -> Self {
+        let mut state = Self::default();
+        state.extend(commands);
+        state
+    }
+}
+
+/// The `Assign` commands for `device_id` that take a camera from `from` to `to`.
+///
+/// Only parameters that differ are included. Parameters that are known in `from` but not in `to`
+/// can't be unset and are left alone. Use [`encode_frame_packets`](crate::encode_frame_packets)
+/// to send the result.
+pub fn diff(from: &CameraState, to: &CameraState, device_id: u8) -> Vec<AddressedCommand> {
+    to.values()
+        .into_iter()
+        .filter(|command| {
+            from.value(command.category(), command.parameter()).as_ref() != Some(command)
+        })
+        .map(|command| AddressedCommand { device_id, command })
+        .collect()
+}
+
+/// [`CameraState`] for every camera seen on the link, keyed by device id.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CameraRegistry {
+    cameras: BTreeMap<u8, CameraState>,
+}
+
+impl CameraRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Folds a command into the state of its camera, adding the camera if it hasn't been seen.
+    /// Broadcast commands only update cameras that are already known.
+    pub fn apply(&mut self, command: &AddressedCommand) {
+        if command.device_id == BROADCAST_DEVICE_ID {
+            self.cameras
+                .values_mut()
+                .for_each(|camera| camera.apply(&command.command));
+        } else {
+            self.register(command.device_id).apply(&command.command);
+        }
+    }
+
+    /// Adds a camera so it receives broadcast commands before it has been addressed directly.
+    pub fn register(&mut self, device_id: u8) -> &mut CameraState {
+        self.cameras.entry(device_id).or_default()
+    }
+
+    pub fn get(&self, device_id: u8) -> Option<&CameraState> {
+        self.cameras.get(&device_id)
+    }
+
+    pub fn get_mut(&mut self, device_id: u8) -> Option<&mut CameraState> {
+        self.cameras.get_mut(&device_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &CameraState)> {
+        self.cameras
+            .iter()
+            .map(|(device_id, camera)| (*device_id, camera))
+    }
+}
+
+impl<'a> Extend<&'a AddressedCommand> for CameraRegistry {
+    fn extend<T: IntoIterator<Item = &'a AddressedCommand>>(&mut self, commands: T) {
+        commands.into_iter().for_each(|command| self.apply(command));
+    }
+}
+
+impl<'a> FromIterator<&'a AddressedCommand> for CameraRegistry {
+    fn from_iter<T: IntoIterator<Item = &'a AddressedCommand>>(commands: T) -> Self {
+        let mut registry = Self::default();
+        registry.extend(commands);
+        registry
+    }
+}
+
+#[cfg(test)]
+mod state_test {
+    use super::*;
+    use crate::{
+        commands::{
+            audio_commands::AudioCommand,
+            color_correction_commands::{ColorCorrectionCommand, RedGreenBlueLuma},
+            lens_commands::LensCommand,
+            video_commands,
+        },
+        FixedPointDecimal, Operation,
+    };
+
+    fn fpd(raw_val: i16) -> FixedPointDecimal {
+        FixedPointDecimal { raw_val }
+    }
+
+    fn lens(device_id: u8, command: LensCommand) -> AddressedCommand {
+        AddressedCommand {
+            device_id,
+            command: Command::Lens(command),
+        }
+    }
+
+    #[test]
+    fn assign_replaces_value() {
+        let mut registry = CameraRegistry::new();
+        registry.apply(&lens(
+            4,
+            LensCommand::ApatureFStop {
+                operation: Operation::Assign,
+                data: fpd(0x1000),
+            },
+        ));
+        registry.apply(&lens(
+            4,
+            LensCommand::ApatureFStop {
+                operation: Operation::Assign,
+                data: fpd(0x0800),
+            },
+        ));
+
+        let camera = registry.get(4).expect("Camera 4 was addressed");
+        assert_eq!(camera.lens.apature_f_stop, Some(fpd(0x0800)));
+        assert_eq!(camera.lens.focus, None);
+    }
+
+    #[test]
+    fn increment_adds_to_value() {
+        let mut state = CameraState::default();
+        state.apply(&Command::Video(video_commands::VideoCommand::ExposureUS {
+            operation: Operation::Assign,
+            data: 1000,
+        }));
+        state.apply(&Command::Video(video_commands::VideoCommand::ExposureUS {
+            operation: Operation::Increment,
+            data: -250,
+        }));
+
+        assert_eq!(state.video.exposure_us, Some(750));
+    }
+
+    #[test]
+    fn increment_saturates_to_bounds() {
+        let mut state = CameraState::default();
+        state.apply(&Command::Lens(LensCommand::Focus {
+            operation: Operation::Assign,
+            data: fpd(0x0700),
+        }));
+        state.apply(&Command::Lens(LensCommand::Focus {
+            operation: Operation::Increment,
+            data: fpd(0x0200),
+        }));
+        assert_eq!(state.lens.focus, Some(fpd(0x0800)));
+
+        state.apply(&Command::Video(video_commands::VideoCommand::ExposureUS {
+            operation: Operation::Assign,
+            data: 10,
+        }));
+        state.apply(&Command::Video(video_commands::VideoCommand::ExposureUS {
+            operation: Operation::Increment,
+            data: -100,
+        }));
+        assert_eq!(state.video.exposure_us, Some(1));
+    }
+
+    #[test]
+    fn increment_struct_data() {
+        let mut state = CameraState::default();
+        state.apply(&Command::Video(
+            video_commands::VideoCommand::ManualWhiteBalance {
+                operation: Operation::Assign,
+                data: video_commands::ManualWhiteBalanceData {
+                    color_temp: 5600,
+                    tint: 0,
+                },
+            },
+        ));
+        state.apply(&Command::Video(
+            video_commands::VideoCommand::ManualWhiteBalance {
+                operation: Operation::Increment,
+                data: video_commands::ManualWhiteBalanceData {
+                    color_temp: -100,
+                    tint: 5,
+                },
+            },
+        ));
+
+        assert_eq!(
+            state.video.manual_white_balance,
+            Some(video_commands::ManualWhiteBalanceData {
+                color_temp: 5500,
+                tint: 5
+            })
+        );
+    }
+
+    #[test]
+    fn increment_struct_data_saturates_to_bounds() {
+        let rgbl = |red, green, blue, luma| RedGreenBlueLuma {
+            red: fpd(red),
+            green: fpd(green),
+            blue: fpd(blue),
+            luma: fpd(luma),
+        };
+        let mut state = CameraState::default();
+        state.apply(&Command::ColorCorrection(
+            ColorCorrectionCommand::LiftAdjust {
+                operation: Operation::Assign,
+                data: rgbl(0x0c00, 0x0000, -0x0c00, 0x0000),
+            },
+        ));
+        state.apply(&Command::ColorCorrection(
+            ColorCorrectionCommand::LiftAdjust {
+                operation: Operation::Increment,
+                data: rgbl(0x0800, 0x0800, 0x0800, -0x2000),
+            },
+        ));
+
+        // Lift runs from -2.0 to 2.0, red and luma stop there.
+        assert_eq!(
+            state.color_correction.lift_adjust,
+            Some(rgbl(0x1000, 0x0800, -0x0400, -0x1000))
+        );
+    }
+
+    #[test]
+    fn increment_unknown_value_stays_unknown() {
+        let mut state = CameraState::default();
+        state.apply(&Command::Lens(LensCommand::Focus {
+            operation: Operation::Increment,
+            data: fpd(0x0100),
+        }));
+
+        assert_eq!(state.lens.focus, None);
+    }
+
+    #[test]
+    fn toggle_flips_bool() {
+        let mut state = CameraState::default();
+        state.apply(&Command::Audio(AudioCommand::PhantomPower {
+            operation: Operation::Assign,
+            data: false,
+        }));
+        state.apply(&Command::Audio(AudioCommand::PhantomPower {
+            operation: Operation::Toggle,
+            data: false,
+        }));
+
+        assert_eq!(state.audio.phantom_power, Some(true));
+    }
+
+    #[test]
+    fn broadcast_applies_to_known_cameras() {
+        let mut registry = CameraRegistry::new();
+        registry.register(1);
+        registry.register(2);
+        registry.apply(&lens(
+            BROADCAST_DEVICE_ID,
+            LensCommand::Focus {
+                operation: Operation::Assign,
+                data: fpd(0x0400),
+            },
+        ));
+
+        assert_eq!(registry.iter().count(), 2);
+        assert!(registry
+            .iter()
+            .all(|(_, camera)| camera.lens.focus == Some(fpd(0x0400))));
+        assert_eq!(registry.get(BROADCAST_DEVICE_ID), None);
+    }
+
+    #[test]
+    fn diff_assigns_differing_values() {
+        let camera_one: CameraState = [
+            Command::Lens(LensCommand::Focus {
+                operation: Operation::Assign,
+                data: fpd(0x0400),
+            }),
+            Command::Video(video_commands::VideoCommand::ISO {
+                operation: Operation::Assign,
+                data: 800,
+            }),
+            Command::Audio(AudioCommand::PhantomPower {
+                operation: Operation::Assign,
+                data: true,
+            }),
+        ]
+        .iter()
+        .collect();
+        let camera_two: CameraState = [
+            Command::Lens(LensCommand::Focus {
+                operation: Operation::Assign,
+                data: fpd(0x0400),
+            }),
+            Command::Video(video_commands::VideoCommand::ISO {
+                operation: Operation::Assign,
+                data: 400,
+            }),
+            Command::Video(video_commands::VideoCommand::ExposureUS {
+                operation: Operation::Assign,
+                data: 1000,
+            }),
+        ]
+        .iter()
+        .collect();
+
+        assert_eq!(
+            diff(&camera_two, &camera_one, 2),
+            vec![
+                AddressedCommand {
+                    device_id: 2,
+                    command: Command::Video(video_commands::VideoCommand::ISO {
+                        operation: Operation::Assign,
+                        data: 800,
+                    }),
+                },
+                AddressedCommand {
+                    device_id: 2,
+                    command: Command::Audio(AudioCommand::PhantomPower {
+                        operation: Operation::Assign,
+                        data: true,
+                    }),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_applied_matches_target() {
+        let commands = [
+            lens(
+                1,
+                LensCommand::ApatureFStop {
+                    operation: Operation::Assign,
+                    data: fpd(0x1000),
+                },
+            ),
+            lens(
+                1,
+                LensCommand::AbsoluteZoomMM {
+                    operation: Operation::Assign,
+                    data: 35,
+                },
+            ),
+        ];
+        let registry: CameraRegistry = commands.iter().collect();
+        let target = registry.get(1).expect("Camera 1 was addressed");
+
+        let mut camera = CameraState::default();
+        camera.extend(
+            diff(&camera, target, 3)
+                .iter()
+                .map(|command| &command.command),
+        );
+        assert_eq!(&camera, target);
+        assert_eq!(diff(&camera, target, 3), vec![]);
+    }
+}
@@ -0,0 +1,189 @@
+use alloc::vec::Vec;
+
+const PROGRAM: u8 = 0b0000_0001;
+const PREVIEW: u8 = 0b0000_0010;
+
+/// Program and preview tally for every camera, as carried in the tally data of an SDI frame.
+///
+/// The raw data has one byte per camera with camera 1 in the first byte. Bit 0 is program, bit 1
+/// is preview and the remaining bits are reserved, they are kept as is so a frame encodes back to
+/// the bytes it was decoded from.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TallyFrame {
+    cameras: Vec<u8>,
+}
+
+/// Tally of a single camera, see [`TallyFrame::iter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CameraTally {
+    pub camera: u8,
+    pub program: bool,
+    pub preview: bool,
+}
+
+impl TallyFrame {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            cameras: bytes.to_vec(),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.cameras
+    }
+
+    /// Number of cameras the frame has a byte for.
+    pub fn len(&self) -> usize {
+        self.cameras.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cameras.is_empty()
+    }
+
+    /// Cameras the frame doesn't cover, including camera 0, are neither on program nor preview.
+    pub fn program(&self, camera: u8) -> bool {
+        self.bits(camera) & PROGRAM != 0
+    }
+
+    pub fn preview(&self, camera: u8) -> bool {
+        self.bits(camera) & PREVIEW != 0
+    }
+
+    /// Grows the frame to cover `camera` if needed. Cameras are numbered from 1, for camera 0
+    /// the frame is left as is and `false` is returned.
+    pub fn set_program(&mut self, camera: u8, on: bool) -> bool {
+        self.set_bits(camera, PROGRAM, on)
+    }
+
+    /// Grows the frame to cover `camera` if needed. Cameras are numbered from 1, for camera 0
+    /// the frame is left as is and `false` is returned.
+    pub fn set_preview(&mut self, camera: u8, on: bool) -> bool {
+        self.set_bits(camera, PREVIEW, on)
+    }
+
+    /// Every camera covered by the frame, starting at camera 1.
+    pub fn iter(&self) -> impl Iterator<Item = CameraTally> + '_ {
+        (1..=u8::MAX)
+            .zip(&self.cameras)
+            .map(|(camera, bits)| CameraTally {
+                camera,
+                program: bits & PROGRAM != 0,
+                preview: bits & PREVIEW != 0,
+            })
+    }
+
+    fn bits(&self, camera: u8) -> u8 {
+        usize::from(camera)
+            .checked_sub(1)
+            .and_then(|idx| self.cameras.get(idx))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn set_bits(&mut self, camera: u8, mask: u8, on: bool) -> bool {
+        let Some(idx) = usize::from(camera).checked_sub(1) else {
+            return false;
+        };
+        if self.cameras.len() <= idx {
+            self.cameras.resize(idx + 1, 0);
+        }
+        if on {
+            self.cameras[idx] |= mask;
+        } else {
+            self.cameras[idx] &= !mask;
+        }
+        true
+    }
+}
+
+impl From<&[u8]> for TallyFrame {
+    fn from(bytes: &[u8]) -> Self {
+        Self::from_bytes(bytes)
+    }
+}
+
+impl From<TallyFrame> for Vec<u8> {
+    fn from(frame: TallyFrame) -> Self {
+        frame.cameras
+    }
+}
+
+#[cfg(test)]
+mod tally_test {
+    use super::*;
+
+    #[test]
+    fn decode_tally() {
+        let frame = TallyFrame::from_bytes(&[0x01, 0x02, 0x00, 0x03]);
+
+        assert!(frame.program(1) && !frame.preview(1));
+        assert!(!frame.program(2) && frame.preview(2));
+        assert!(!frame.program(3) && !frame.preview(3));
+        assert!(frame.program(4) && frame.preview(4));
+        assert!(!frame.program(0) && !frame.program(5));
+        assert_eq!(frame.len(), 4);
+    }
+
+    #[test]
+    fn set_tally_grows_frame() {
+        let mut frame = TallyFrame::new();
+        frame.set_program(3, true);
+        frame.set_preview(1, true);
+        frame.set_preview(3, true);
+        frame.set_program(3, false);
+
+        assert_eq!(frame.as_bytes(), [0x02, 0x00, 0x02]);
+    }
+
+    #[test]
+    fn set_camera_zero() {
+        let mut frame = TallyFrame::from_bytes(&[0x01]);
+
+        assert!(!frame.set_program(0, true));
+        assert!(!frame.set_preview(0, true));
+        assert!(frame.set_preview(1, true));
+        assert_eq!(frame.as_bytes(), [0x03]);
+    }
+
+    #[test]
+    fn reserved_bits_round_trip() {
+        let bytes = [0xf1, 0x06];
+        let mut frame = TallyFrame::from(&bytes[..]);
+        frame.set_program(1, false);
+        frame.set_program(2, true);
+
+        assert_eq!(Vec::from(frame), [0xf0, 0x07]);
+    }
+
+    #[test]
+    fn iterate_cameras() {
+        let frame = TallyFrame::from_bytes(&[0x01, 0x00, 0x02]);
+
+        assert_eq!(
+            frame.iter().collect::<Vec<_>>(),
+            [
+                CameraTally {
+                    camera: 1,
+                    program: true,
+                    preview: false
+                },
+                CameraTally {
+                    camera: 2,
+                    program: false,
+                    preview: false
+                },
+                CameraTally {
+                    camera: 3,
+                    program: false,
+                    preview: true
+                },
+            ]
+        );
+    }
+}
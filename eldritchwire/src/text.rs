@@ -0,0 +1,628 @@
+//! Text syntax for commands, e.g. `cam 4 lens.focus = 0.15`.
+//!
+//! A command is `<group>.<parameter>` named after the snake cased command group and variant,
+//! followed by `= value` to assign, `+= value` to increment a number, `toggle` for bool parameters
+//! or nothing for commands without data. Struct payloads are written with their `data(..)` fields,
+//! `color_correction.gain_adjust = {red: 1.0, green: 1.0, blue: 1.0, luma: 1.0}`, and strings are
+//! double quoted.
+
+use crate::{
+    commands::{
+        audio_commands::AudioCommand, color_correction_commands::ColorCorrectionCommand,
+        configuration_commands::ConfigurationCommand, display_commands::DisplayCommand,
+        lens_commands::LensCommand, media_commands::MediaCommand,
+        metadata_commands::MetadataCommand, output_commands::OutputCommand,
+        ptz_control_commands::PtzControlCommand, reference_commands::ReferenceCommand,
+        tally_commands::TallyCommand, video_commands::VideoCommand, Command,
+    },
+    AddressedCommand, EldritchError, FixedPointDecimal, Operation,
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt, str::FromStr};
+
+/// A command split into its parts, the group has already been matched by [`Command::from_str`].
+pub(crate) struct TextCommand<'a> {
+    pub parameter: &'a str,
+    path: &'a str,
+    operation: Option<Operation>,
+    value: Option<TextValue<'a>>,
+}
+
+impl<'a> TextCommand<'a> {
+    fn parse(text: &'a str) -> Result<(&'a str, Self), EldritchError> {
+        let text = text.trim();
+        let path_end = text
+            .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '.'))
+            .unwrap_or(text.len());
+        let (path, rest) = text.split_at(path_end);
+        let (group, parameter) = path
+            .split_once('.')
+            .ok_or_else(|| invalid_text("Expected <group>.<parameter>", path))?;
+
+        let rest = rest.trim();
+        let (operation, value) = if rest.is_empty() {
+            (None, None)
+        } else if rest == "toggle" {
+            (Some(Operation::Toggle), None)
+        } else if let Some(value) = rest.strip_prefix("+=") {
+            (Some(Operation::Increment), Some(TextValue::parse(value)?))
+        } else if let Some(value) = rest.strip_prefix('=') {
+            (Some(Operation::Assign), Some(TextValue::parse(value)?))
+        } else {
+            return Err(invalid_text("Expected `=`, `+=` or `toggle`", rest));
+        };
+
+        Ok((
+            group,
+            Self {
+                parameter,
+                path,
+                operation,
+                value,
+            },
+        ))
+    }
+
+    pub fn error(&self, message: &'static str) -> EldritchError {
+        invalid_text(message, self.path)
+    }
+
+    /// For commands without data.
+    pub fn expect_void(&self) -> Result<(), EldritchError> {
+        match self.operation {
+            None => Ok(()),
+            Some(_) => Err(self.error("Command doesn't take a value")),
+        }
+    }
+
+    /// The operation and its value, toggling a bool stands in a `false` value. Bools and strings
+    /// can't be incremented.
+    pub fn operation_and_value(
+        &self,
+        data_type: u8,
+    ) -> Result<(Operation, &TextValue<'a>), EldritchError> {
+        const TOGGLE: TextValue<'static> = TextValue::Scalar("false");
+        let is_bool = data_type == 0x00;
+        match (&self.operation, &self.value) {
+            (Some(Operation::Toggle), _) if is_bool => Ok((Operation::Toggle, &TOGGLE)),
+            (Some(Operation::Toggle), _) => Err(self.error("Only bool parameters can be toggled")),
+            (Some(Operation::Increment), _) if is_bool => {
+                Err(self.error("Bool parameters can't be incremented, use `toggle`"))
+            }
+            (Some(Operation::Increment), _) if data_type == 0x05 => {
+                Err(self.error("String parameters can't be incremented"))
+            }
+            (Some(operation), Some(value)) => Ok((operation.clone(), value)),
+            _ => Err(self.error("Command needs a value")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum TextValue<'a> {
+    Scalar(&'a str),
+    String(String),
+    Struct(Vec<(&'a str, TextValue<'a>)>),
+}
+
+impl<'a> TextValue<'a> {
+    fn parse(text: &'a str) -> Result<Self, EldritchError> {
+        let (value, rest) = Self::parse_value(text)?;
+        if !rest.trim().is_empty() {
+            return Err(invalid_text("Unexpected text after value", rest.trim()));
+        }
+        Ok(value)
+    }
+
+    fn parse_value(text: &'a str) -> Result<(Self, &'a str), EldritchError> {
+        let text = text.trim_start();
+        if let Some(rest) = text.strip_prefix('{') {
+            Self::parse_struct(rest)
+        } else if let Some(rest) = text.strip_prefix('"') {
+            Self::parse_string(rest)
+        } else {
+            let end = text
+                .find(|ch: char| ch.is_whitespace() || matches!(ch, '{' | '}' | ',' | ':'))
+                .unwrap_or(text.len());
+            if end == 0 {
+                return Err(invalid_text("Expected a value", text));
+            }
+            Ok((TextValue::Scalar(&text[..end]), &text[end..]))
+        }
+    }
+
+    fn parse_struct(mut text: &'a str) -> Result<(Self, &'a str), EldritchError> {
+        let mut fields = Vec::new();
+        loop {
+            text = text.trim_start();
+            if let Some(rest) = text.strip_prefix('}') {
+                return Ok((TextValue::Struct(fields), rest));
+            }
+            let (name, rest) = text
+                .split_once(':')
+                .ok_or_else(|| invalid_text("Expected `field: value`", text))?;
+            let (value, rest) = Self::parse_value(rest)?;
+            fields.push((name.trim(), value));
+
+            let rest = rest.trim_start();
+            text = match rest.strip_prefix(',') {
+                Some(rest) => rest,
+                None if rest.starts_with('}') => rest,
+                None => return Err(invalid_text("Expected `,` or `}`", rest)),
+            };
+        }
+    }
+
+    fn parse_string(text: &'a str) -> Result<(Self, &'a str), EldritchError> {
+        let mut string = String::new();
+        let mut chars = text.char_indices();
+        while let Some((idx, ch)) = chars.next() {
+            match ch {
+                '"' => return Ok((TextValue::String(string), &text[idx + 1..])),
+                '\\' => match chars.next() {
+                    Some((_, escaped @ ('"' | '\\'))) => string.push(escaped),
+                    _ => return Err(invalid_text("Unknown escape in string", &text[idx..])),
+                },
+                ch => string.push(ch),
+            }
+        }
+        Err(invalid_text("Unterminated string", text))
+    }
+
+    /// A field of a struct value, every field in `names` has to be present and nothing else.
+    pub fn field(&self, names: &[&str], name: &str) -> Result<&TextValue<'a>, EldritchError> {
+        let TextValue::Struct(fields) = self else {
+            return Err(invalid_text("Expected a struct value", name));
+        };
+        if let Some((unknown, _)) = fields.iter().find(|(field, _)| !names.contains(field)) {
+            return Err(invalid_text("Unknown field", unknown));
+        }
+        fields
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| value)
+            .ok_or_else(|| invalid_text("Missing field", name))
+    }
+
+    fn scalar(&self) -> Result<&'a str, EldritchError> {
+        match self {
+            TextValue::Scalar(scalar) => Ok(scalar),
+            TextValue::String(string) => Err(invalid_text("Expected a number or bool", string)),
+            TextValue::Struct(_) => Err(invalid_text("Expected a number or bool", "{..}")),
+        }
+    }
+}
+
+pub(crate) trait FromTextValue: Sized {
+    fn from_text_value(value: &TextValue) -> Result<Self, EldritchError>;
+}
+
+macro_rules! int_from_text_value {
+    ($($int:ty),*) => {
+        $(
+            impl FromTextValue for $int {
+                fn from_text_value(value: &TextValue) -> Result<Self, EldritchError> {
+                    let scalar = value.scalar()?;
+                    scalar.parse().map_err(|_| invalid_text("Expected an integer", scalar))
+                }
+            }
+        )*
+    };
+}
+
+int_from_text_value!(i8, i16, i32, i64);
+
+impl FromTextValue for bool {
+    fn from_text_value(value: &TextValue) -> Result<Self, EldritchError> {
+        match value.scalar()? {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            scalar => Err(invalid_text("Expected `true` or `false`", scalar)),
+        }
+    }
+}
+
+impl FromTextValue for FixedPointDecimal {
+    fn from_text_value(value: &TextValue) -> Result<Self, EldritchError> {
+        let scalar = value.scalar()?;
+        FixedPointDecimal::from_real_val(
+            scalar
+                .parse()
+                .map_err(|_| invalid_text("Expected a decimal", scalar))?,
+        )
+    }
+}
+
+impl FromTextValue for String {
+    fn from_text_value(value: &TextValue) -> Result<Self, EldritchError> {
+        match value {
+            TextValue::String(string) => Ok(string.clone()),
+            TextValue::Scalar(scalar) => Err(invalid_text("Expected a quoted string", scalar)),
+            TextValue::Struct(_) => Err(invalid_text("Expected a quoted string", "{..}")),
+        }
+    }
+}
+
+pub(crate) trait ToTextValue {
+    fn fmt_text_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+macro_rules! display_to_text_value {
+    ($($ty:ty),*) => {
+        $(
+            impl ToTextValue for $ty {
+                fn fmt_text_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(f, "{self}")
+                }
+            }
+        )*
+    };
+}
+
+display_to_text_value!(bool, i8, i16, i32, i64);
+
+impl ToTextValue for FixedPointDecimal {
+    fn fmt_text_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Debug always keeps the decimal point, `1.0` rather than `1`.
+        write!(f, "{:?}", self.get_shortest_val())
+    }
+}
+
+impl ToTextValue for String {
+    fn fmt_text_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"")?;
+        for ch in self.chars() {
+            if matches!(ch, '"' | '\\') {
+                write!(f, "\\")?;
+            }
+            write!(f, "{ch}")?;
+        }
+        write!(f, "\"")
+    }
+}
+
+/// Writes ` = value`, ` += value` or ` toggle`.
+pub(crate) fn fmt_operation(
+    f: &mut fmt::Formatter<'_>,
+    operation: &Operation,
+    value: impl FnOnce(&mut fmt::Formatter<'_>) -> fmt::Result,
+) -> fmt::Result {
+    match operation {
+        Operation::Assign => write!(f, " = ")?,
+        Operation::Increment => write!(f, " += ")?,
+        Operation::Toggle => return write!(f, " toggle"),
+    }
+    value(f)
+}
+
+pub(crate) fn fmt_struct(
+    f: &mut fmt::Formatter<'_>,
+    fields: &[(&str, &dyn ToTextValue)],
+) -> fmt::Result {
+    write!(f, "{{")?;
+    for (idx, (name, value)) in fields.iter().enumerate() {
+        if idx > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{name}: ")?;
+        value.fmt_text_value(f)?;
+    }
+    write!(f, "}}")
+}
+
+fn invalid_text(message: &'static str, text: &str) -> EldritchError {
+    EldritchError::InvalidText {
+        message,
+        text: text.to_string(),
+    }
+}
+
+impl FromStr for Command {
+    type Err = EldritchError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (group, command) = TextCommand::parse(text)?;
+        match group {
+            LensCommand::NAME => LensCommand::from_text(&command).map(Command::Lens),
+            VideoCommand::NAME => VideoCommand::from_text(&command).map(Command::Video),
+            AudioCommand::NAME => AudioCommand::from_text(&command).map(Command::Audio),
+            OutputCommand::NAME => OutputCommand::from_text(&command).map(Command::Output),
+            DisplayCommand::NAME => DisplayCommand::from_text(&command).map(Command::Display),
+            TallyCommand::NAME => TallyCommand::from_text(&command).map(Command::Tally),
+            ReferenceCommand::NAME => ReferenceCommand::from_text(&command).map(Command::Reference),
+            ConfigurationCommand::NAME => {
+                ConfigurationCommand::from_text(&command).map(Command::Configuration)
+            }
+            ColorCorrectionCommand::NAME => {
+                ColorCorrectionCommand::from_text(&command).map(Command::ColorCorrection)
+            }
+            MediaCommand::NAME => MediaCommand::from_text(&command).map(Command::Media),
+            PtzControlCommand::NAME => {
+                PtzControlCommand::from_text(&command).map(Command::PtzControl)
+            }
+            MetadataCommand::NAME => MetadataCommand::from_text(&command).map(Command::Metadata),
+            _ => Err(invalid_text("Unknown command group", group)),
+        }
+    }
+}
+
+/// `Unknown` commands are written as `unknown(category, parameter)` followed by their data type,
+/// operation and raw payload. They can't be parsed back.
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::Lens(cmd) => cmd.fmt(f),
+            Command::Video(cmd) => cmd.fmt(f),
+            Command::Audio(cmd) => cmd.fmt(f),
+            Command::Output(cmd) => cmd.fmt(f),
+            Command::Display(cmd) => cmd.fmt(f),
+            Command::Tally(cmd) => cmd.fmt(f),
+            Command::Reference(cmd) => cmd.fmt(f),
+            Command::Configuration(cmd) => cmd.fmt(f),
+            Command::ColorCorrection(cmd) => cmd.fmt(f),
+            Command::Media(cmd) => cmd.fmt(f),
+            Command::PtzControl(cmd) => cmd.fmt(f),
+            Command::Metadata(cmd) => cmd.fmt(f),
+            Command::Unknown {
+                category,
+                parameter,
+                data_type,
+                operation,
+                payload,
+            } => write!(
+                f,
+                "unknown({category:#04x}, {parameter:#04x}) data type: {data_type:#04x}, \
+                 operation: {operation:#04x}, payload: {payload:02x?}"
+            ),
+        }
+    }
+}
+
+impl FromStr for AddressedCommand {
+    type Err = EldritchError;
+
+    /// `cam <device id> <command>`, device id 255 is a broadcast.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let rest = text
+            .strip_prefix("cam")
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+            .ok_or_else(|| invalid_text("Expected `cam <device id>`", text))?
+            .trim_start();
+        let (device_id, command) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+        Ok(AddressedCommand {
+            device_id: device_id
+                .parse()
+                .map_err(|_| invalid_text("Expected a device id from 0 to 255", device_id))?,
+            command: command.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for AddressedCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cam {} {}", self.device_id, self.command)
+    }
+}
+
+#[cfg(test)]
+mod text_test {
+    use super::*;
+    use crate::commands::{
+        audio_commands::AudioCommand,
+        color_correction_commands::RedGreenBlueLuma,
+        metadata_commands::MetadataCommand,
+        video_commands::{ManualWhiteBalanceData, VideoCommand},
+    };
+    use alloc::format;
+
+    fn fpd(real_val: f32) -> FixedPointDecimal {
+        FixedPointDecimal::from_real_val(real_val).expect("Value is in range")
+    }
+
+    fn round_trip(text: &str, command: AddressedCommand) {
+        assert_eq!(text.parse::<AddressedCommand>(), Ok(command.clone()));
+        assert_eq!(command.to_string(), text);
+    }
+
+    #[test]
+    fn assign_fixed_point() {
+        round_trip(
+            "cam 4 lens.focus = 0.15",
+            AddressedCommand {
+                device_id: 4,
+                command: Command::Lens(LensCommand::Focus {
+                    operation: Operation::Assign,
+                    data: fpd(0.15),
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn increment_int() {
+        round_trip(
+            "cam 255 video.gain += 2",
+            AddressedCommand {
+                device_id: 255,
+                command: Command::Video(VideoCommand::Gain {
+                    operation: Operation::Increment,
+                    data: 2,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn toggle_bool() {
+        round_trip(
+            "cam 1 audio.phantom_power toggle",
+            AddressedCommand {
+                device_id: 1,
+                command: Command::Audio(AudioCommand::PhantomPower {
+                    operation: Operation::Toggle,
+                    data: false,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn void_command() {
+        round_trip(
+            "cam 2 lens.instantaneous_auto_focus",
+            AddressedCommand {
+                device_id: 2,
+                command: Command::Lens(LensCommand::InstantaneousAutoFocus),
+            },
+        );
+    }
+
+    #[test]
+    fn struct_payload() {
+        round_trip(
+            "cam 3 color_correction.gain_adjust = {red: 1.25, green: 1.0, blue: 0.875, luma: 1.0}",
+            AddressedCommand {
+                device_id: 3,
+                command: Command::ColorCorrection(ColorCorrectionCommand::GainAdjust {
+                    operation: Operation::Assign,
+                    data: RedGreenBlueLuma {
+                        red: fpd(1.25),
+                        green: fpd(1.0),
+                        blue: fpd(0.875),
+                        luma: fpd(1.0),
+                    },
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn struct_payload_any_order_and_spacing() {
+        assert_eq!(
+            "video.manual_white_balance={tint:-3,color_temp:5600}".parse::<Command>(),
+            Ok(Command::Video(VideoCommand::ManualWhiteBalance {
+                operation: Operation::Assign,
+                data: ManualWhiteBalanceData {
+                    color_temp: 5600,
+                    tint: -3,
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn string_payload() {
+        round_trip(
+            r#"cam 1 metadata.director = "Stanley \"The\" Kubrick""#,
+            AddressedCommand {
+                device_id: 1,
+                command: Command::Metadata(MetadataCommand::Director {
+                    operation: Operation::Assign,
+                    data: String::from("Stanley \"The\" Kubrick"),
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn every_group_round_trips() {
+        for text in [
+            "cam 1 lens.apature_ordinal += 3",
+            "cam 1 video.recording_format = {file_frame_rate: 24, sensor_frame_rate: 48, \
+             frame_width: 1920, frame_height: 1080, flags: 5}",
+            "cam 1 audio.phantom_power = true",
+            "cam 1 output.overlays = {frame_guide_style: 2, frame_guide_opacity: 80, \
+             safe_area_percentage: 90, grid_style: 1}",
+            "cam 1 display.overlays_enabled = 772",
+            "cam 1 tally.rear_tally_brightness = 0.5",
+            "cam 1 reference.offset += -42",
+            "cam 1 configuration.system_language = \"en\"",
+            "cam 1 color_correction.correction_reset_default",
+            "cam 1 media.codec = {basic_codec: 3, codec_varient: 1}",
+            "cam 1 ptz_control.pan_tilt_velocity = {pan_velocity: 0.5, tilt_velocity: -0.5}",
+            "cam 1 metadata.take = {number: 7, tag: 1}",
+        ] {
+            let command = text.parse::<AddressedCommand>();
+            assert_eq!(
+                command.map(|command| command.to_string()).as_deref(),
+                Ok(text)
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_text() {
+        let error = |text: &str| match text.parse::<AddressedCommand>() {
+            Err(EldritchError::InvalidText { message, text }) => format!("{message}: {text}"),
+            other => panic!("Expected invalid text for {text}, got {other:?}"),
+        };
+
+        assert_eq!(
+            error("4 lens.focus = 0.1"),
+            "Expected `cam <device id>`: 4 lens.focus = 0.1"
+        );
+        assert_eq!(
+            error("cam 256 lens.focus = 0.1"),
+            "Expected a device id from 0 to 255: 256"
+        );
+        assert_eq!(
+            error("cam 4 camera.focus = 0.1"),
+            "Unknown command group: camera"
+        );
+        assert_eq!(
+            error("cam 4 lens.fcus = 0.1"),
+            "Unknown parameter: lens.fcus"
+        );
+        assert_eq!(
+            error("cam 4 lens.focus"),
+            "Command needs a value: lens.focus"
+        );
+        assert_eq!(
+            error("cam 4 lens.focus toggle"),
+            "Only bool parameters can be toggled: lens.focus"
+        );
+        assert_eq!(
+            error("cam 1 audio.phantom_power += true"),
+            "Bool parameters can't be incremented, use `toggle`: audio.phantom_power"
+        );
+        assert_eq!(
+            error("cam 4 metadata.director += \"Kubrick\""),
+            "String parameters can't be incremented: metadata.director"
+        );
+        assert_eq!(error("cam 4 lens.focus = fast"), "Expected a decimal: fast");
+        assert_eq!(
+            error("cam 4 lens.focus - 0.1"),
+            "Expected `=`, `+=` or `toggle`: - 0.1"
+        );
+        assert_eq!(
+            error("cam 4 video.manual_white_balance = {color_temp: 5600}"),
+            "Missing field: tint"
+        );
+        assert_eq!(
+            error("cam 4 video.manual_white_balance = {color_temp: 5600, tint: 0, hue: 1}"),
+            "Unknown field: hue"
+        );
+        assert_eq!(
+            error("cam 4 metadata.director = \"Kubrick"),
+            "Unterminated string: Kubrick"
+        );
+    }
+
+    #[test]
+    fn out_of_bounds_value() {
+        assert_eq!(
+            "lens.focus = 16.0".parse::<Command>(),
+            Err(EldritchError::DataOutOfBounds)
+        );
+        #[cfg(feature = "bounds-checked")]
+        assert_eq!(
+            "lens.focus = 1.5".parse::<Command>(),
+            Err(EldritchError::DataOutOfBounds)
+        );
+    }
+}
@@ -7,24 +7,87 @@ pub fn command_group(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
 
-    let commands: Result<Vec<_>> = if let syn::Data::Enum(data) = &input.data {
-        data.variants.iter().map(handle_variant_attr).collect()
+    let variants = if let syn::Data::Enum(data) = &input.data {
+        &data.variants
     } else {
-        Err(Error::new_spanned(&input, "CommandGroup must be an enum"))
+        return Error::new_spanned(&input, "CommandGroup must be an enum")
+            .to_compile_error()
+            .into();
     };
+    let commands: Result<Vec<_>> = variants.iter().map(handle_variant_attr).collect();
 
-    let parse_command_fn = build_parse_command_fn(name, &commands.expect("Commands are expected"));
+    let category = match handle_group_attr(&input) {
+        Ok(category) => category,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let commands = match commands {
+        Ok(commands) => commands,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let parse_command_fn = build_parse_command_fn(name, &commands);
+    let encode_command_fn = build_encode_command_fn(name, &commands);
+    let state_struct = build_state_struct(name, variants, &commands);
+    let text_impl = build_text_impl(name, &commands);
+    let variant_names = commands.iter().map(|command| command.name);
+    let parameters = commands.iter().map(|command| command.parameter);
 
     quote! {
         #parse_command_fn
+        #encode_command_fn
+        #state_struct
+        #text_impl
+
+        impl #name {
+            pub const CATEGORY: u8 = #category;
+
+            pub fn parameter(&self) -> u8 {
+                match self {
+                    #(#name::#variant_names { .. } => #parameters,)*
+                }
+            }
+
+            pub fn to_command_bytes(&self) -> Result<alloc::vec::Vec<u8>, EldritchError> {
+                let mut buffer = alloc::vec![Self::CATEGORY];
+                encode_command(self, &mut buffer)?;
+                Ok(buffer)
+            }
+        }
     }
     .into()
 }
 
+fn handle_group_attr(input: &DeriveInput) -> Result<u8> {
+    let mut category = None;
+
+    for attr in &input.attrs {
+        if attr.path().is_ident("command") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("category") {
+                    let content;
+                    parenthesized!(content in meta.input);
+                    let lit: syn::LitInt = content.parse()?;
+                    category = Some(lit.base10_parse()?);
+                }
+                Ok(())
+            })?;
+        }
+    }
+
+    category.ok_or_else(|| {
+        Error::new_spanned(
+            &input.ident,
+            "CommandGroup requires a #[command(category(..))] attribute",
+        )
+    })
+}
+
 fn handle_variant_attr(variant: &syn::Variant) -> Result<CommandMetaData<'_>> {
     let mut parameter = 0;
     let mut data_type = None;
     let mut data = None;
+    let mut discard_data = false;
+    let mut max_length = None;
 
     #[cfg(feature = "bounds-checked")]
     let mut bounds = DataBounds {
@@ -48,6 +111,9 @@ fn handle_variant_attr(variant: &syn::Variant) -> Result<CommandMetaData<'_>> {
                     parenthesized!(content in meta.input);
                     let lit: syn::LitInt = content.parse()?;
                     let val: u8 = lit.base10_parse()?;
+                    if !matches!(val, 0x00..=0x05 | 0x80) {
+                        return Err(Error::new_spanned(lit, "Unknown data type"));
+                    }
                     data_type = Some(val);
                 }
 
@@ -65,6 +131,17 @@ fn handle_variant_attr(variant: &syn::Variant) -> Result<CommandMetaData<'_>> {
                     data = Some(elements);
                 }
 
+                if meta.path.is_ident("discard_data") {
+                    discard_data = true;
+                }
+
+                if meta.path.is_ident("max_length") {
+                    let content;
+                    parenthesized!(content in meta.input);
+                    let lit: syn::LitInt = content.parse()?;
+                    max_length = Some(lit.base10_parse()?);
+                }
+
                 #[cfg(feature = "bounds-checked")]
                 if meta.path.is_ident("bounds") {
                     meta.parse_nested_meta(|inner_meta| {
@@ -88,11 +165,34 @@ fn handle_variant_attr(variant: &syn::Variant) -> Result<CommandMetaData<'_>> {
             })?;
         }
     }
+    if max_length.is_some() && data_type != Some(0x05) {
+        return Err(Error::new_spanned(
+            &variant.ident,
+            "max_length is only supported on string data",
+        ));
+    }
+
+    // Struct payloads are built with the type of the variant's `data` field.
+    let data_ty = variant
+        .fields
+        .iter()
+        .find(|field| field.ident.as_ref().is_some_and(|ident| ident == "data"))
+        .map(|field| &field.ty);
+    if data.is_some() && data_ty.is_none() {
+        return Err(Error::new_spanned(
+            &variant.ident,
+            "data(..) needs a `data` field to hold the fields",
+        ));
+    }
+
     let builder = CommandMetaData::builder()
         .name(&variant.ident)
         .parameter(parameter)
         .data_type(data_type)
-        .data(data);
+        .data(data)
+        .data_ty(data_ty)
+        .discard_data(discard_data)
+        .max_length(max_length);
 
     #[cfg(feature = "bounds-checked")]
     let builder = builder.bounds(bounds);
@@ -106,7 +206,7 @@ fn build_parse_command_fn(name: &Ident, commands: &Vec<CommandMetaData>) -> Toke
             let variant_name = variant.name;
             let param = &variant.parameter;
             let variant_parser = build_variant_parser(name, variant);
-            let arm_return = if variant.data_type.is_some() {
+            let arm_return = if variant.data_type.is_some() && !variant.discard_data {
                 quote! { #variant_parser, }
             } else {
                 quote! { Ok(#name::#variant_name), }
@@ -119,9 +219,10 @@ fn build_parse_command_fn(name: &Ident, commands: &Vec<CommandMetaData>) -> Toke
         pub fn parse_command(command_data: CommandData) -> Result<#name, EldritchError> {
             match command_data.parameter() {
                 #(#match_branches)*
-                _ => Err(EldritchError::InvalidCommandData {
-                    message: String::from("No command matches found"),
-                    data: command_data.raw().to_vec()
+                _ => Err(EldritchError::UnknownParameter {
+                    category: *command_data.category(),
+                    parameter: *command_data.parameter(),
+                    raw: command_data.raw().to_vec()
                 }),
             }
         }
@@ -132,73 +233,75 @@ fn build_variant_parser(name: &Ident, command: &CommandMetaData) -> TokenStream
     let command_name = &command.name;
     let data_type = command.data_type;
 
-    // TODO this is duplicate code and should be removed.
-    if let Some(0x00) = data_type {
-        return quote! {
-            Ok(#name::#command_name{
-                operation: if *command_data.operation() == 0 {
-                    Operation::Assign
-                } else {
-                    return Err(EldritchError::InvalidCommandData {
-                        message: String::from("Bad operation for data type, can't increment a bool"),
-                        data: command_data.raw().to_vec()
-                    });
-                },
-                data: command_data.data_buff()[0] != 0,
-            })
-        };
-    }
     let (data_parser, data_size) = match data_type {
         Some(0x00) => {
             return quote! {
                 Ok(#name::#command_name{
-                    operation: if *command_data.operation() == 0 {
-                        Operation::Assign
-                    } else {
-                        return Err(EldritchError::InvalidCommandData {
-                            message: String::from("Bad operation for data type, cant' increment a bool"),
-                            data: command_data.raw().to_vec()
-                        });
+                    operation: match *command_data.operation() {
+                        0 => Operation::Assign,
+                        // A bool only toggles, the encoder writes `Toggle` as operation 1.
+                        1 => Operation::Toggle,
+                        operation => return Err(EldritchError::InvalidOperation {
+                            operation,
+                            data_type: 0x00,
+                            raw: command_data.raw().to_vec()
+                        }),
                     },
-                    data: command_data.data_buff()[0] != 0,
+                    data: *command_data.data_buff().first().ok_or_else(|| EldritchError::InvalidCommandData {
+                        message: "Missing data for bool",
+                        data: command_data.raw().to_vec()
+                    })? != 0,
                 })
-            }
+            };
         }
         Some(0x01) => (quote! { i8::from_le_bytes }, 1),
         Some(0x02) => (quote! { i16::from_le_bytes }, 2),
         Some(0x03) => (quote! { i32::from_le_bytes }, 4),
         Some(0x04) => (quote! { i64::from_le_bytes }, 8),
         Some(0x05) => {
-            return quote! {
+            #[cfg(feature = "bounds-checked")]
+            let length_check = if let Some(max_length) = command.max_length {
+                quote! {
+                    if data.len() > #max_length {
+                        return Err(EldritchError::DataOutOfBounds);
+                    }
+                }
+            } else {
+                quote! {}
+            };
+            #[cfg(not(feature = "bounds-checked"))]
+            let length_check = quote! {};
+
+            return quote! {{
+                if *command_data.operation() != 0 {
+                    return Err(EldritchError::InvalidOperation {
+                        operation: *command_data.operation(),
+                        data_type: 0x05,
+                        raw: command_data.raw().to_vec()
+                    });
+                }
+                // Fixed length fields are padded out with NUL bytes.
+                let data = command_data.data_buff();
+                let data = &data[..data.iter().rposition(|byte| *byte != 0).map_or(0, |end| end + 1)];
+                #length_check
                 Ok(#name::#command_name {
-                    operation: if *command_data.operation() == 0 {
-                        Operation::Assign
-                    } else {
-                        return Err(EldritchError::InvalidCommandData {
-                            message: String::from("Bad operation for data type, can't increment a string"),
-                            data: command_data.raw().to_vec()
-                        });
-                    },
-                    data: String::from_utf8(command_data.data_buff().try_into()
-                              .map_err(|_| EldritchError::InvalidCommandData {
-                                  message: String::from("Unable to convert data to vec"),
-                                  data: command_data.raw().to_vec()
-                              })?)
+                    operation: Operation::Assign,
+                    data: alloc::string::String::from_utf8(data.to_vec())
                         .map_err(|_| EldritchError::InvalidCommandData {
-                            message: String::from("Bad String data"),
+                            message: "Bad String data",
                             data: command_data.raw().to_vec()
                         })?
                 })
-            }
+            }};
         }
         Some(0x80) => (quote! { FixedPointDecimal::from_data }, 2),
-        Some(_) => todo!("Unknown data type"),
+        Some(_) => unreachable!("Data types are validated when parsing the command attribute"),
         None => (quote! {}, 0),
     };
 
     #[cfg(feature = "bounds-checked")]
     let bounds_check = if let Some(bounds) = &command.bounds {
-        build_bounds_check(data_type, bounds)
+        build_bounds_check(data_type, bounds, command.data.as_deref())
     } else {
         quote! {}
     };
@@ -227,8 +330,7 @@ fn build_variant_parser(name: &Ident, command: &CommandMetaData) -> TokenStream
     };
 
     let data_parser = if let Some(data_map) = &command.data {
-        let data_struct_name = format!("{}Data", command_name);
-        let data_struct_ident = Ident::new(&data_struct_name, proc_macro2::Span::call_site());
+        let data_struct_ty = command.data_ty;
         let data_elements: Vec<_> = data_map
             .iter()
             .enumerate()
@@ -238,9 +340,9 @@ fn build_variant_parser(name: &Ident, command: &CommandMetaData) -> TokenStream
                 let error_msg = format!(
                     "Failed to convert raw data into correct shape for inner data point: {ident}"
                 );
-                quote! { #ident: #data_parser(data[#s_idx..#e_idx].try_into()
+                quote! { #ident: #data_parser(data.get(#s_idx..#e_idx).unwrap_or_default().try_into()
                     .map_err(|_| EldritchError::InvalidCommandData {
-                        message: String::from(#error_msg),
+                        message: #error_msg,
                         data: command_data.raw().to_vec()
                     })?),
                 }
@@ -248,7 +350,7 @@ fn build_variant_parser(name: &Ident, command: &CommandMetaData) -> TokenStream
             .collect();
         quote! {
             let data = command_data.data_buff();
-            let data = #data_struct_ident {
+            let data = #data_struct_ty {
                 #(#data_elements)*
             };
             #data_assignment
@@ -260,7 +362,7 @@ fn build_variant_parser(name: &Ident, command: &CommandMetaData) -> TokenStream
                 #data_assignment
             } else {
                 Err(EldritchError::InvalidCommandData {
-                    message: String::from("Failed to convert raw data into correct shape"),
+                    message: "Failed to convert raw data into correct shape",
                     data: command_data.raw().to_vec(),
                 })
             }
@@ -271,17 +373,449 @@ fn build_variant_parser(name: &Ident, command: &CommandMetaData) -> TokenStream
         if *command_data.data_type() == #data_type {
             #data_parser
         } else {
-            Err(EldritchError::InvalidCommandData {
-                message: String::from("Invalid Data type for command"),
-                data: command_data.raw().to_vec(),
+            Err(EldritchError::InvalidDataType {
+                data_type: *command_data.data_type(),
+                expected: #data_type,
+                raw: command_data.raw().to_vec(),
             })
         }
     }
 }
 
-#[cfg(feature = "bounds-checked")]
-fn build_bounds_check(data_type: Option<u8>, bounds: &DataBounds) -> TokenStream {
+fn build_encode_command_fn(name: &Ident, commands: &[CommandMetaData]) -> TokenStream {
+    let match_branches: Vec<proc_macro2::TokenStream> = commands
+        .iter()
+        .map(|variant| {
+            let variant_name = variant.name;
+            let param = &variant.parameter;
+            match variant.data_type {
+                Some(data_type) if variant.discard_data => {
+                    // The payload was thrown away when parsing, write back a zeroed single value.
+                    let data_size = data_type_size(data_type);
+                    quote! {
+                        #name::#variant_name => {
+                            buffer.extend_from_slice(&[#param, #data_type, 0x00]);
+                            buffer.extend_from_slice(&[0x00; #data_size]);
+                        }
+                    }
+                }
+                Some(data_type) => {
+                    let data_encoder = build_variant_encoder(variant);
+                    quote! {
+                        #name::#variant_name { operation, data } => {
+                            buffer.extend_from_slice(&[
+                                #param,
+                                #data_type,
+                                match operation {
+                                    Operation::Assign => 0x00,
+                                    Operation::Increment | Operation::Toggle => 0x01,
+                                },
+                            ]);
+                            #data_encoder
+                        }
+                    }
+                }
+                None => quote! {
+                    #name::#variant_name => buffer.extend_from_slice(&[#param, 0x00, 0x00]),
+                },
+            }
+        })
+        .collect();
+
+    quote! {
+        pub fn encode_command(
+            command: &#name,
+            buffer: &mut alloc::vec::Vec<u8>,
+        ) -> Result<(), EldritchError> {
+            match command {
+                #(#match_branches)*
+            }
+            Ok(())
+        }
+    }
+}
+
+fn build_variant_encoder(command: &CommandMetaData) -> TokenStream {
+    let data_type = command.data_type.unwrap_or_default();
+    let data_map = &command.data;
+
+    // Same limit the parser enforces, so we never emit a frame we would reject ourselves.
+    #[cfg(feature = "bounds-checked")]
+    let length_check = if let Some(max_length) = command.max_length {
+        quote! {
+            if data.len() > #max_length {
+                return Err(EldritchError::DataOutOfBounds);
+            }
+        }
+    } else {
+        quote! {}
+    };
+    #[cfg(not(feature = "bounds-checked"))]
+    let length_check = quote! {};
+
+    let encode_value = |value: TokenStream| match data_type {
+        0x00 => quote! { buffer.push(u8::from(#value)); },
+        0x01..=0x04 => quote! { buffer.extend_from_slice(&#value.to_le_bytes()); },
+        0x05 => quote! {
+            #length_check
+            buffer.extend_from_slice(#value.as_bytes());
+        },
+        0x80 => quote! { buffer.extend_from_slice(&#value.to_data()); },
+        _ => unreachable!("Data types are validated when parsing the command attribute"),
+    };
+
+    if let Some(data_map) = data_map {
+        let data_elements: Vec<_> = data_map
+            .iter()
+            .map(|ident| encode_value(quote! { data.#ident }))
+            .collect();
+        quote! { #(#data_elements)* }
+    } else if data_type == 0x00 {
+        encode_value(quote! { *data })
+    } else {
+        encode_value(quote! { data })
+    }
+}
+
+/// Builds the text syntax for the group, `from_text` and `Display` in the form
+/// `group.parameter = value`, see the `text` module.
+fn build_text_impl(name: &Ident, commands: &[CommandMetaData]) -> TokenStream {
+    let group_name = snake_case(name.to_string().trim_end_matches("Command"));
+
+    let (parse_arms, display_arms): (Vec<_>, Vec<_>) = commands
+        .iter()
+        .map(|command| {
+            let variant_name = command.name;
+            let parameter_name = snake_case(&variant_name.to_string());
+            let path = format!("{group_name}.{parameter_name}");
+
+            let data_type = match command.data_type {
+                Some(data_type) if !command.discard_data => data_type,
+                _ => {
+                    return (
+                        quote! {
+                            #parameter_name => {
+                                text.expect_void()?;
+                                Ok(#name::#variant_name)
+                            }
+                        },
+                        quote! { #name::#variant_name => write!(f, #path), },
+                    );
+                }
+            };
+
+            let (data, fmt_data) = if let Some(data_map) = &command.data {
+                let data_struct_ty = command.data_ty;
+                let field_names: Vec<_> = data_map.iter().map(|ident| ident.to_string()).collect();
+                let all_fields = quote! { &[#(#field_names),*] };
+                (
+                    quote! {
+                        #data_struct_ty {
+                            #(#data_map: crate::text::FromTextValue::from_text_value(
+                                value.field(#all_fields, #field_names)?
+                            )?,)*
+                        }
+                    },
+                    quote! {
+                        crate::text::fmt_struct(f, &[#((#field_names, &data.#data_map)),*])
+                    },
+                )
+            } else {
+                let data_ty = match data_type {
+                    0x00 => quote! { bool },
+                    0x01 => quote! { i8 },
+                    0x02 => quote! { i16 },
+                    0x03 => quote! { i32 },
+                    0x04 => quote! { i64 },
+                    0x05 => quote! { alloc::string::String },
+                    _ => quote! { FixedPointDecimal },
+                };
+                (
+                    quote! { <#data_ty as crate::text::FromTextValue>::from_text_value(value)? },
+                    quote! { crate::text::ToTextValue::fmt_text_value(data, f) },
+                )
+            };
+
+            #[cfg(feature = "bounds-checked")]
+            let bounds_check = match (&command.bounds, command.max_length) {
+                (Some(bounds), _) => {
+                    build_bounds_check(Some(data_type), bounds, command.data.as_deref())
+                }
+                (None, Some(max_length)) => quote! {
+                    if data.len() > #max_length {
+                        Err(EldritchError::DataOutOfBounds)
+                    } else
+                },
+                (None, None) => quote! {},
+            };
+            #[cfg(not(feature = "bounds-checked"))]
+            let bounds_check = quote! {};
+
+            (
+                quote! {
+                    #parameter_name => {
+                        let (operation, value) = text.operation_and_value(#data_type)?;
+                        let data = #data;
+                        #bounds_check {
+                            Ok(#name::#variant_name { operation, data })
+                        }
+                    }
+                },
+                quote! {
+                    #name::#variant_name { operation, data } => {
+                        write!(f, #path)?;
+                        crate::text::fmt_operation(f, operation, |f| #fmt_data)
+                    }
+                },
+            )
+        })
+        .unzip();
+
+    quote! {
+        impl #name {
+            /// Name of the group in the text syntax.
+            pub const NAME: &'static str = #group_name;
+
+            pub(crate) fn from_text(text: &crate::text::TextCommand) -> Result<#name, EldritchError> {
+                match text.parameter {
+                    #(#parse_arms)*
+                    _ => Err(text.error("Unknown parameter")),
+                }
+            }
+        }
+
+        impl core::fmt::Display for #name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                match self {
+                    #(#display_arms)*
+                }
+            }
+        }
+    }
+}
+
+/// Builds `{Group}State`, the last known value of every parameter in the group that carries data.
+fn build_state_struct<'a>(
+    name: &Ident,
+    variants: impl IntoIterator<Item = &'a syn::Variant>,
+    commands: &[CommandMetaData],
+) -> TokenStream {
+    let state_name = Ident::new(
+        &format!("{}State", name.to_string().trim_end_matches("Command")),
+        name.span(),
+    );
+
+    let (fields, (apply_arms, (value_arms, params))): (Vec<_>, (Vec<_>, (Vec<_>, Vec<_>))) =
+        variants
+            .into_iter()
+            .zip(commands)
+            .filter(|(_, command)| !command.discard_data)
+            .filter_map(|(variant, command)| {
+                let data_ty = variant.fields.iter().find_map(|field| {
+                    field
+                        .ident
+                        .as_ref()
+                        .filter(|ident| *ident == "data")
+                        .map(|_| &field.ty)
+                })?;
+                let data_type = command.data_type?;
+                let variant_name = command.name;
+                let field = Ident::new(&snake_case(&variant_name.to_string()), variant_name.span());
+
+                let increment = build_state_increment(data_type, command);
+                let toggle = if data_type == 0x00 {
+                    quote! {
+                        Operation::Toggle => {
+                            if let Some(current) = &mut self.#field {
+                                *current = !*current;
+                            }
+                        }
+                    }
+                } else {
+                    quote! {}
+                };
+                let catch_all = if increment.is_empty() || toggle.is_empty() {
+                    quote! { _ => {} }
+                } else {
+                    quote! {}
+                };
+
+                let param = command.parameter;
+                Some((
+                    quote! { pub #field: Option<#data_ty>, },
+                    (
+                        quote! {
+                            #name::#variant_name { operation, data } => match operation {
+                                Operation::Assign => self.#field = Some(data.clone()),
+                                #increment
+                                #toggle
+                                #catch_all
+                            },
+                        },
+                        (
+                            quote! {
+                                #param => self.#field.clone().map(|data| #name::#variant_name {
+                                    operation: Operation::Assign,
+                                    data,
+                                }),
+                            },
+                            param,
+                        ),
+                    ),
+                ))
+            })
+            .unzip();
+
+    quote! {
+        #[derive(Clone, Debug, Default, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub struct #state_name {
+            #(#fields)*
+        }
+
+        impl #state_name {
+            /// `Assign` replaces the value, `Increment` adds to it saturating to the parameter
+            /// bounds and `Toggle` flips a bool. Parameters that were never assigned stay `None`.
+            pub fn apply(&mut self, command: &#name) {
+                match command {
+                    #(#apply_arms)*
+                    #[allow(unreachable_patterns)]
+                    _ => {}
+                }
+            }
+
+            /// The known value of a parameter as an `Assign` command.
+            pub fn value(&self, parameter: u8) -> Option<#name> {
+                match parameter {
+                    #(#value_arms)*
+                    _ => None,
+                }
+            }
+
+            /// Every known value as an `Assign` command, in parameter order.
+            pub fn values(&self) -> alloc::vec::Vec<#name> {
+                [#(#params),*]
+                    .into_iter()
+                    .filter_map(|parameter| self.value(parameter))
+                    .collect()
+            }
+        }
+    }
+}
+
+fn build_state_increment(data_type: u8, command: &CommandMetaData) -> TokenStream {
+    let field = Ident::new(&snake_case(&command.name.to_string()), command.name.span());
+
+    #[cfg(feature = "bounds-checked")]
+    let bounds = command
+        .bounds
+        .as_ref()
+        .map(|bounds| (bounds.lower.as_ref(), bounds.upper.as_ref()));
+    #[cfg(not(feature = "bounds-checked"))]
+    let bounds: Option<(Option<&syn::Lit>, Option<&syn::Lit>)> = None;
+
+    let update = if let Some(data_map) = &command.data {
+        // Struct payloads share the parameter's bounds, every field is clamped on its own.
+        let elements = data_map.iter().map(|ident| {
+            let data = match data_type {
+                0x80 => quote! { &data.#ident },
+                _ => quote! { data.#ident },
+            };
+            let sum = build_clamped_sum(data_type, bounds, quote! { current.#ident }, data);
+            quote! { current.#ident = #sum; }
+        });
+        quote! { #(#elements)* }
+    } else {
+        let data = match data_type {
+            0x80 => quote! { data },
+            _ => quote! { *data },
+        };
+        match data_type {
+            0x01..=0x04 | 0x80 => {
+                let sum = build_clamped_sum(data_type, bounds, quote! { current }, data);
+                quote! { *current = #sum; }
+            }
+            _ => return quote! {},
+        }
+    };
+
+    quote! {
+        Operation::Increment => {
+            if let Some(current) = &mut self.#field {
+                #update
+            }
+        }
+    }
+}
+
+/// `current + data` for an integer or fixed point value, saturating at the parameter bounds when
+/// it has any and at the limits of the data type otherwise.
+fn build_clamped_sum(
+    data_type: u8,
+    bounds: Option<(Option<&syn::Lit>, Option<&syn::Lit>)>,
+    current: TokenStream,
+    data: TokenStream,
+) -> TokenStream {
+    match (data_type, bounds) {
+        (0x80, None) => quote! { #current.saturating_add(#data) },
+        (0x80, Some((lower, upper))) => {
+            let lower = lower.map_or_else(|| quote! { -16.0 }, |lit| quote! { #lit });
+            let upper = upper.map_or_else(|| quote! { 15.9995 }, |lit| quote! { #lit });
+            quote! { #current.saturating_add(#data).clamp_real(#lower, #upper) }
+        }
+        (_, None) => quote! { #current.saturating_add(#data) },
+        (_, Some((lower, upper))) => {
+            let int_ty = match data_type {
+                0x01 => quote! { i8 },
+                0x02 => quote! { i16 },
+                0x03 => quote! { i32 },
+                _ => quote! { i64 },
+            };
+            let lower = lower.map_or_else(|| quote! { #int_ty::MIN }, |lit| quote! { #lit });
+            let upper = upper.map_or_else(|| quote! { #int_ty::MAX }, |lit| quote! { #lit });
+            quote! { #current.saturating_add(#data).clamp(#lower, #upper) }
+        }
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::new();
+    for (idx, ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() && idx > 0 {
+            let prev = chars[idx - 1];
+            let next_is_lower = chars.get(idx + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+        snake.extend(ch.to_lowercase());
+    }
+    snake
+}
+
+fn data_type_size(data_type: u8) -> usize {
     match data_type {
+        0x00 | 0x01 => 1,
+        0x02 | 0x80 => 2,
+        0x03 => 4,
+        0x04 => 8,
+        _ => panic!("Data type has no fixed size"),
+    }
+}
+
+#[cfg(feature = "bounds-checked")]
+/// Rejects data outside of `bounds` with `DataOutOfBounds`. For a struct payload the bounds apply
+/// to each of its `fields`.
+fn build_bounds_check(
+    data_type: Option<u8>,
+    bounds: &DataBounds,
+    fields: Option<&[Ident]>,
+) -> TokenStream {
+    let (range, real_val) = match data_type {
         Some(0x00) => panic!("Bounds, not supported on Bool"),
         Some(0x01) => {
             let lower = if let Some(lower) = &bounds.lower {
@@ -308,11 +842,7 @@ fn build_bounds_check(data_type: Option<u8>, bounds: &DataBounds) -> TokenStream
                 i8::MAX
             };
 
-            quote! {
-                if !(#lower..=#upper).contains(&data) {
-                    Err(EldritchError::DataOutOfBounds)
-                } else
-            }
+            (quote! { #lower..=#upper }, false)
         }
         Some(0x02) => {
             let lower = if let Some(lower) = &bounds.lower {
@@ -339,11 +869,7 @@ fn build_bounds_check(data_type: Option<u8>, bounds: &DataBounds) -> TokenStream
                 i16::MAX
             };
 
-            quote! {
-                if !(#lower..=#upper).contains(&data) {
-                    Err(EldritchError::DataOutOfBounds)
-                } else
-            }
+            (quote! { #lower..=#upper }, false)
         }
         Some(0x03) => {
             let lower = if let Some(lower) = &bounds.lower {
@@ -370,11 +896,7 @@ fn build_bounds_check(data_type: Option<u8>, bounds: &DataBounds) -> TokenStream
                 i32::MAX
             };
 
-            quote! {
-                if !(#lower..=#upper).contains(&data) {
-                    Err(EldritchError::DataOutOfBounds)
-                } else
-            }
+            (quote! { #lower..=#upper }, false)
         }
         Some(0x04) => {
             let lower = if let Some(lower) = &bounds.lower {
@@ -401,11 +923,7 @@ fn build_bounds_check(data_type: Option<u8>, bounds: &DataBounds) -> TokenStream
                 i64::MAX
             };
 
-            quote! {
-                if !(#lower..=#upper).contains(&data) {
-                    Err(EldritchError::DataOutOfBounds)
-                } else
-            }
+            (quote! { #lower..=#upper }, false)
         }
         Some(0x05) => panic!("String does not support bounds Checking"),
         Some(0x80) => {
@@ -433,14 +951,28 @@ fn build_bounds_check(data_type: Option<u8>, bounds: &DataBounds) -> TokenStream
                 15.9995
             };
 
-            quote! {
-                if !(#lower..=#upper).contains(&data.get_real_val()) {
-                    Err(EldritchError::DataOutOfBounds)
-                } else
-            }
+            (quote! { #lower..=#upper }, true)
         }
         Some(_) => panic!("Unsupported Data Type"),
-        None => quote! {},
+        None => return quote! {},
+    };
+
+    let values = match fields {
+        Some(fields) => fields.iter().map(|field| quote! { data.#field }).collect(),
+        None => vec![quote! { data }],
+    };
+    let out_of_bounds = values.iter().map(|value| {
+        if real_val {
+            quote! { !(#range).contains(&#value.get_real_val()) }
+        } else {
+            quote! { !(#range).contains(&#value) }
+        }
+    });
+
+    quote! {
+        if #(#out_of_bounds)||* {
+            Err(EldritchError::DataOutOfBounds)
+        } else
     }
 }
 
@@ -457,6 +989,9 @@ struct CommandMetaData<'a> {
     parameter: u8,
     data_type: Option<u8>,
     data: Option<Vec<Ident>>,
+    data_ty: Option<&'a syn::Type>,
+    discard_data: bool,
+    max_length: Option<usize>,
     #[cfg(feature = "bounds-checked")]
     bounds: Option<DataBounds>,
 }
@@ -467,6 +1002,9 @@ struct CommandMetaDataBuilder<'a> {
     parameter: Option<u8>,
     data_type: Option<u8>,
     data: Option<Vec<Ident>>,
+    data_ty: Option<&'a syn::Type>,
+    discard_data: bool,
+    max_length: Option<usize>,
     #[cfg(feature = "bounds-checked")]
     bounds: Option<DataBounds>,
 }
@@ -498,6 +1036,21 @@ impl<'a> CommandMetaDataBuilder<'a> {
         self
     }
 
+    pub fn data_ty(mut self, data_ty: Option<&'a syn::Type>) -> Self {
+        self.data_ty = data_ty;
+        self
+    }
+
+    pub fn discard_data(mut self, discard_data: bool) -> Self {
+        self.discard_data = discard_data;
+        self
+    }
+
+    pub fn max_length(mut self, max_length: Option<usize>) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
     #[cfg(feature = "bounds-checked")]
     pub fn bounds(mut self, bounds: DataBounds) -> Self {
         self.bounds = if bounds.upper.is_some() || bounds.lower.is_some() {
@@ -514,6 +1067,9 @@ impl<'a> CommandMetaDataBuilder<'a> {
             parameter: self.parameter.expect("parameter is required"),
             data_type: self.data_type,
             data: self.data,
+            data_ty: self.data_ty,
+            discard_data: self.discard_data,
+            max_length: self.max_length,
             #[cfg(feature = "bounds-checked")]
             bounds: self.bounds,
         })
@@ -526,12 +1082,37 @@ mod macro_tests {
     use proc_macro2::Span;
     use syn::parse_quote;
 
+    #[test]
+    fn handle_group_attr_test() {
+        let input: DeriveInput = parse_quote! {
+            #[command(category(0x08))]
+            enum ColorCorrectionCommand {
+                #[command(parameter(0x07))]
+                CorrectionResetDefault,
+            }
+        };
+
+        assert_eq!(handle_group_attr(&input).unwrap(), 8);
+    }
+
+    #[test]
+    fn handle_group_attr_missing_category_test() {
+        let input: DeriveInput = parse_quote! {
+            enum ColorCorrectionCommand {
+                #[command(parameter(0x07))]
+                CorrectionResetDefault,
+            }
+        };
+
+        assert!(handle_group_attr(&input).is_err());
+    }
+
     #[test]
     fn handle_variant_single_attr_test() {
         let input: syn::ItemEnum = parse_quote! {
             enum LensCommands {
                 #[command(parameter(0x00), data_type(128), data(red, blue))]
-                Focus(Operation, FixedPointDecimal),
+                Focus { operation: Operation, data: RedBlue },
             }
         };
 
@@ -548,6 +1129,9 @@ mod macro_tests {
                     Ident::new("red", Span::call_site()),
                     Ident::new("blue", Span::call_site())
                 ]),
+                data_ty: Some(&parse_quote!(RedBlue)),
+                discard_data: false,
+                max_length: None,
                 #[cfg(feature = "bounds-checked")]
                 bounds: None,
             }
@@ -573,9 +1157,88 @@ mod macro_tests {
                 parameter: 0,
                 data_type: Some(128),
                 data: None,
+                data_ty: None,
+                discard_data: false,
+                max_length: None,
                 #[cfg(feature = "bounds-checked")]
                 bounds: None,
             }
         );
     }
+
+    #[test]
+    fn handle_variant_data_without_data_field_test() {
+        let input: syn::ItemEnum = parse_quote! {
+            enum LensCommands {
+                #[command(parameter(0x00), data_type(128), data(red, blue))]
+                Focus(Operation, RedBlue),
+            }
+        };
+
+        let variant = input.variants.get(0).unwrap();
+        let output = handle_variant_attr(variant);
+
+        assert_eq!(
+            output.unwrap_err().to_string(),
+            "data(..) needs a `data` field to hold the fields"
+        );
+    }
+
+    #[test]
+    fn handle_variant_max_length_test() {
+        let input: syn::ItemEnum = parse_quote! {
+            enum MetadataCommand {
+                #[command(parameter(0x02), data_type(5), max_length(5))]
+                Scene(Operation, String),
+            }
+        };
+
+        let variant = input.variants.get(0).unwrap();
+        let output = handle_variant_attr(variant);
+
+        assert_eq!(output.unwrap().max_length, Some(5));
+    }
+
+    #[test]
+    fn handle_variant_max_length_not_string_test() {
+        let input: syn::ItemEnum = parse_quote! {
+            enum MetadataCommand {
+                #[command(parameter(0x00), data_type(2), max_length(5))]
+                Reel(Operation, i16),
+            }
+        };
+
+        let variant = input.variants.get(0).unwrap();
+        let output = handle_variant_attr(variant);
+
+        assert_eq!(
+            output.unwrap_err().to_string(),
+            "max_length is only supported on string data"
+        );
+    }
+
+    #[test]
+    fn snake_case_test() {
+        assert_eq!(snake_case("Focus"), "focus");
+        assert_eq!(snake_case("ApatureFStop"), "apature_f_stop");
+        assert_eq!(snake_case("AbsoluteZoomMM"), "absolute_zoom_mm");
+        assert_eq!(snake_case("NDFilterStop"), "nd_filter_stop");
+        assert_eq!(snake_case("DisplayLUT"), "display_lut");
+        assert_eq!(snake_case("ISO"), "iso");
+    }
+
+    #[test]
+    fn handle_variant_unknown_data_type_test() {
+        let input: syn::ItemEnum = parse_quote! {
+            enum LensCommands {
+                #[command(parameter(0x00), data_type(6))]
+                Focus(Operation, FixedPointDecimal),
+            }
+        };
+
+        let variant = input.variants.get(0).unwrap();
+        let output = handle_variant_attr(variant);
+
+        assert_eq!(output.unwrap_err().to_string(), "Unknown data type");
+    }
 }